@@ -3,7 +3,6 @@
 use std::path::PathBuf;
 
 use ggez::{event, GameResult};
-use env_logger;
 use grid::*;
 
 use rust_othello::*;
@@ -19,7 +18,7 @@ fn main() -> GameResult {
     env_logger::init();
 
     // Here we use a ContextBuilder to setup metadata about our game. First the title and author
-    let (ctx, event_loop)  = ggez::ContextBuilder::new(
+    let (mut ctx, event_loop)  = ggez::ContextBuilder::new(
         env!("CARGO_PKG_NAME"),
         env!("CARGO_PKG_AUTHORS"))
         .add_resource_path::<PathBuf>(
@@ -33,12 +32,14 @@ fn main() -> GameResult {
         // Now we get to set the size of the window,
         // which we use our SCREEN_SIZE constant from earlier to help with
         .window_mode(ggez::conf::WindowMode::default()
-                         .dimensions(SCREEN_SIZE.0 as f32, SCREEN_SIZE.1 as f32),
+                         .dimensions(SCREEN_SIZE.0 as f32, SCREEN_SIZE.1 as f32)
+                         .resizable(true),
         )
         // And finally we attempt to build the context and create the window.
         // If it fails, we panic with the message "Failed to build ggez context"
         .build()
         .expect("Failed to build ggez context");
+    App::fit_screen(&mut ctx)?;
 
     // Create our board for example
     let grid = grid![[E,B,B,B,B,B,B,E]
@@ -51,9 +52,10 @@ fn main() -> GameResult {
                                 [E,B,B,B,B,B,B,E]];
     let board = Board::set_board(grid);
 
-    // Next we create a new instance of our Game struct, which implements EventHandler
+    // Next we create a new instance of our Game struct, shown over the main menu
     let state = Othello::new(PLAYER_ONE, PLAYER_TWO).set_board(board);
+    let app = App::new(Config::default(), Theme::default()).with_game(state);
 
-    // And finally we actually run our game, passing in our context, event_loop and state.
-    event::run(ctx, event_loop, state)
+    // And finally we actually run our game, passing in our context, event_loop and app.
+    event::run(ctx, event_loop, app)
 }
@@ -0,0 +1,403 @@
+//! Analysis of a finished game: the engine evaluate every move and look for the mistakes
+//!
+//! ```
+//! use rust_othello::*;
+//!
+//! let game = SavedGame { moves: String::from("f5d6c3d3c4"), ..SavedGame::default() };
+//! let analysis = Analysis::new(&game, Engine::new(2)).unwrap();
+//! assert_eq!(analysis.moves().len(), 5);
+//! println!("{}", analysis.to_text());
+//! ```
+
+use std::fmt::Write as _;
+use std::fs;
+use std::io;
+use std::path::Path;
+
+use ggez::{Context, GameResult};
+use ggez::event::{KeyCode, KeyMods};
+use ggez::graphics::{self, Color, Rect};
+use glam::Vec2;
+use log::{error, info};
+use serde_json::json;
+
+use crate::*;
+
+/// Depth of the engine of an analysis when it is not chosen
+pub const DEFAULT_ANALYSIS_DEPTH: u8 = 4;
+
+/// A move that lose more than this (in points of the engine) is a blunder
+pub const BLUNDER_ERROR: i32 = 30;
+
+/// Biggest error counted for a move, e.g. a move that lose a won game
+pub const MAX_MOVE_ERROR: i32 = 100;
+
+/// Name of the reports saved from the window in the data directory
+pub const ANALYSIS_FILE_NAMES: [&str; 2] = ["analysis.txt", "analysis.json"];
+
+/// Color of the frame around the best move in the analysis screen
+pub(crate) const BEST_MOVE_COLOR: Color = Color::new(0.0, 0.8, 0.0, 1.0);
+
+/// The evaluation of a move of the game
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct MoveReport {
+    /// Number of the move, the first move is 1
+    pub number: usize,
+    /// Who played the move
+    pub piece: Piece,
+    pub played: GridPosition,
+    /// Score of the played move for the player of the move
+    pub score: i32,
+    /// The best move found by the engine and its score
+    pub best: GridPosition,
+    pub best_score: i32,
+}
+
+/// The evaluation of every move of a game
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Analysis {
+    /// Names of the players
+    players: (String, String),
+    /// The board before the first move then after each move
+    boards: Vec<Board>,
+    moves: Vec<MoveReport>,
+    depth: u8,
+}
+
+impl MoveReport {
+    /// Return how much the move lose compared to the best move, at most [`MAX_MOVE_ERROR`]
+    pub fn error(&self) -> i32 {
+        (self.best_score - self.score).clamp(0, MAX_MOVE_ERROR)
+    }
+
+    /// Verify if the move lose more than [`BLUNDER_ERROR`]
+    pub fn is_blunder(&self) -> bool {
+        self.error() > BLUNDER_ERROR
+    }
+
+    /// Return the score of the move for the black player
+    pub fn black_score(&self) -> i32 {
+        if self.piece == Piece::WHITE { -self.score } else { self.score }
+    }
+}
+
+impl Analysis {
+    /// Replay a game and evaluate each move with the engine
+    ///
+    /// An engine limited by time search at the depth of [`MAX_AI_LEVEL`].
+    pub fn new(game: &SavedGame, engine: Engine) -> Result<Self, NotationError> {
+        let (mut board, mut turn) = game.start()?;
+        let mut boards = vec![board.clone()];
+        let mut moves = Vec::new();
+        for (index, position) in game.moves()?.into_iter().enumerate() {
+            let scores = engine.evaluate_moves(&board, if board.can_play(turn) { turn } else { turn.next() });
+            let piece = play_transcript_move(&mut board, turn, index, position)?;
+            let score = scores.iter().find(|(other, _)| *other == position).map_or(0, |(_, score)| *score);
+            // The first best move is kept, like the engine do
+            let (best, best_score) = scores.iter().fold((position, score), |best, (other, other_score)| {
+                if *other_score > best.1 { (*other, *other_score) } else { best }
+            });
+            moves.push(MoveReport { number: index + 1, piece, played: position, score, best, best_score });
+            boards.push(board.clone());
+            turn = piece.next();
+        }
+
+        let (player_black, player_white) = game.players();
+        Ok(Analysis {
+            players: (player_black.to_string(), player_white.to_string()),
+            boards,
+            moves,
+            depth: engine.depth().min(MAX_AI_LEVEL),
+        })
+    }
+
+    /// Return the evaluation of each move
+    pub fn moves(&self) -> &[MoveReport] {
+        &self.moves
+    }
+
+    /// Return the board before the move `index` (starting at 0), or at the end of the game
+    pub fn board_before(&self, index: usize) -> &Board {
+        &self.boards[index.min(self.boards.len() - 1)]
+    }
+
+    /// Return the sum of the errors of a player
+    pub fn total_error(&self, piece: Piece) -> i32 {
+        self.moves.iter().filter(|report| report.piece == piece).map(MoveReport::error).sum()
+    }
+
+    /// Return the number of blunders of a player
+    pub fn blunders(&self, piece: Piece) -> usize {
+        self.moves.iter().filter(|report| report.piece == piece && report.is_blunder()).count()
+    }
+
+    /// Return the score of each move for the black player, to draw the evaluation graph
+    pub fn evaluations(&self) -> Vec<i32> {
+        self.moves.iter().map(MoveReport::black_score).collect()
+    }
+
+    /// Return the name of a player
+    fn player(&self, piece: Piece) -> &str {
+        if piece == Piece::WHITE { &self.players.1 } else { &self.players.0 }
+    }
+
+    /// Return the summary of a player, e.g. "Marie Curie (WHITE): error 42, 1 blunder(s)"
+    pub fn summary(&self, piece: Piece) -> String {
+        format!("{}: error {}, {} blunder(s)", self.player(piece), self.total_error(piece), self.blunders(piece))
+    }
+
+    /// Write the report as a text table, the blunders are marked with "??"
+    pub fn to_text(&self) -> String {
+        let mut text = String::new();
+        // `write!` in a `String` cannot fail
+        let _ = writeln!(text, "Analysis at depth {} (the scores are for the player of the move)", self.depth);
+        let _ = writeln!(text, "  # Player  Move  Score  Best  Score  Error");
+        for report in &self.moves {
+            let _ = writeln!(text, "{:>3} {:<6}  {:<4}  {:>5}  {:<4}  {:>5}  {:>5}{}",
+                report.number, report.piece.to_string(), report.played.to_notation(), report.score,
+                report.best.to_notation(), report.best_score, report.error(),
+                if report.is_blunder() { " ??" } else { "" });
+        }
+        let _ = writeln!(text, "{}", self.summary(Piece::BLACK));
+        let _ = writeln!(text, "{}", self.summary(Piece::WHITE));
+        text
+    }
+
+    /// Write the report as JSON
+    pub fn to_json(&self) -> String {
+        let moves: Vec<_> = self.moves.iter().map(|report| json!({
+            "number": report.number,
+            "player": report.piece.to_string(),
+            "move": report.played.to_notation(),
+            "score": report.score,
+            "best": report.best.to_notation(),
+            "best_score": report.best_score,
+            "error": report.error(),
+            "blunder": report.is_blunder(),
+        })).collect();
+        let player = |piece: Piece| json!({
+            "name": self.player(piece),
+            "total_error": self.total_error(piece),
+            "blunders": self.blunders(piece),
+        });
+        let report = json!({
+            "depth": self.depth,
+            "moves": moves,
+            "black": player(Piece::BLACK),
+            "white": player(Piece::WHITE),
+        });
+        format!("{:#}\n", report)
+    }
+
+    /// Write the report in a file: JSON if the extension is `.json`, else a text table
+    pub fn save(&self, path: &Path) -> io::Result<()> {
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        let is_json = path.extension().is_some_and(|extension| extension == "json");
+        fs::write(path, if is_json { self.to_json() } else { self.to_text() })
+    }
+}
+
+/// Draw a line graph of the evaluation of each move in `area`
+///
+/// The scores are for the black player, the top of the graph is `limit` and the bottom `-limit`.
+/// The move `selected` (starting at 0) is marked with a vertical line.
+pub fn draw_evaluation_graph(ctx: &mut Context, theme: &Theme, area: Rect, evaluations: &[i32], limit: i32, selected: Option<usize>) -> GameResult {
+    let mut builder = graphics::MeshBuilder::new();
+    builder.rectangle(graphics::DrawMode::fill(), area, Color::new(0.0, 0.0, 0.0, 0.6))?;
+    let middle = area.y + area.h / 2.0;
+    builder.line(&[Vec2::new(area.left(), middle), Vec2::new(area.right(), middle)], 1.0, theme.grid_color)?;
+
+    let step = area.w / evaluations.len().max(1) as f32;
+    let point = |index: usize, score: i32| {
+        let score = score.clamp(-limit, limit) as f32 / limit.max(1) as f32;
+        Vec2::new(area.x + (index as f32 + 0.5) * step, middle - score * area.h / 2.0)
+    };
+    if let Some(index) = selected.filter(|index| *index < evaluations.len()) {
+        let x = point(index, 0).x;
+        builder.line(&[Vec2::new(x, area.top()), Vec2::new(x, area.bottom())], 1.0, theme.cursor_color)?;
+    }
+    let points: Vec<Vec2> = evaluations.iter().enumerate().map(|(index, score)| point(index, *score)).collect();
+    if points.len() >= 2 {
+        builder.line(&points, 2.0, theme.piece_colors.0)?;
+    }
+    for point in points {
+        builder.circle(graphics::DrawMode::fill(), point, 3.0, 1.0, theme.piece_colors.1)?;
+    }
+    let mesh = builder.build(ctx)?;
+    graphics::draw(ctx, &mesh, graphics::DrawParam::default())
+}
+
+/// The screen of an analysis: the board before a move, the list of the moves and the graph
+#[derive(Debug, Clone)]
+pub struct AnalysisView {
+    analysis: Analysis,
+    /// The shown move (starting at 0)
+    index: usize,
+    theme: Theme,
+}
+
+impl AnalysisView {
+    /// Show an analysis, from the first move
+    pub fn new(analysis: Analysis, theme: Theme) -> Self {
+        AnalysisView { analysis, index: 0, theme }
+    }
+
+    /// Show another move, the index stay on the moves
+    fn select(&mut self, index: isize) {
+        let last = self.analysis.moves.len().saturating_sub(1);
+        self.index = index.clamp(0, last as isize) as usize;
+    }
+
+    /// Return the text of the panel: the summary and the moves around the shown one
+    fn panel_text(&self, lines: usize) -> String {
+        let mut text = format!("Analysis (depth {})\n\n{}\n{}\n\n",
+            self.analysis.depth, self.analysis.summary(Piece::BLACK), self.analysis.summary(Piece::WHITE));
+        let first = self.index.saturating_sub(lines / 2).min(self.analysis.moves.len().saturating_sub(lines));
+        for report in self.analysis.moves.iter().skip(first).take(lines) {
+            let marker = if report.number == self.index + 1 { ">" } else { " " };
+            let _ = writeln!(text, "{}{:>3}. {} {:>4} {:<3}best {} {:>4}{}",
+                marker, report.number, piece_char(report.piece), report.score, report.played.to_notation(),
+                report.best.to_notation(), report.best_score, if report.is_blunder() { " ??" } else { "" });
+        }
+        text
+    }
+
+    /// Write the report in text and in JSON in the data directory
+    fn export(&self) {
+        for file_name in ANALYSIS_FILE_NAMES {
+            match SavedGame::data_path(file_name) {
+                Some(path) => match self.analysis.save(&path) {
+                    Ok(()) => info!("The analysis is saved in {}", path.display()),
+                    Err(err) => error!("Cannot save the analysis in {}: {}", path.display(), err),
+                },
+                None => error!("No directory to save the analysis"),
+            }
+        }
+    }
+}
+
+impl Scene for AnalysisView {
+    /// Draw the board before the shown move (the played move and the best one are framed) and the panel
+    fn draw(&mut self, ctx: &mut Context, _world: &World) -> GameResult {
+        graphics::clear(ctx, self.theme.background_color);
+        let layout = Layout::from_context(ctx);
+        let theme = Theme { valid_moves_color: None, ..self.theme.clone() };
+        let board = self.analysis.board_before(self.index);
+        board.draw_animated(ctx, layout.board, Piece::EMPTY, &theme, None)?;
+        if let Some(report) = self.analysis.moves.get(self.index) {
+            let best_theme = Theme { cursor_color: BEST_MOVE_COLOR, ..theme.clone() };
+            board.draw_cursor(ctx, &best_theme, report.best, layout.board)?;
+            board.draw_cursor(ctx, &theme, report.played, layout.board)?;
+        }
+
+        // The graph use the bottom quarter of the panel, the text the rest
+        let area = layout.panel;
+        let margin = 10.0 * layout.scale;
+        let graph = Rect::new(area.x + margin, area.bottom() - area.h / 4.0, area.w - 2.0 * margin, area.h / 4.0 - margin);
+        let background = graphics::MeshBuilder::new()
+            .rectangle(graphics::DrawMode::fill(), area, Color::new(0.0, 0.0, 0.0, 0.6))?
+            .build(ctx)?;
+        graphics::draw(ctx, &background, graphics::DrawParam::default())?;
+        draw_evaluation_graph(ctx, &self.theme, graph, &self.analysis.evaluations(), MAX_MOVE_ERROR, Some(self.index))?;
+
+        let font_size = layout.font_size(self.theme.font_scale);
+        let lines = ((graph.y - area.y - 2.0 * margin) / (1.2 * font_size)) as usize;
+        let font = graphics::Font::new(ctx, self.theme.font_path.as_ref())?;
+        let mut text = graphics::Text::new((self.panel_text(lines.saturating_sub(5)), font, font_size));
+        text.set_bounds(Vec2::new(area.w - 2.0 * margin, graph.y - area.y - margin), graphics::Align::Left);
+        graphics::draw(ctx, &text, (Vec2::new(area.x + margin, area.y + margin), Color::WHITE))
+    }
+
+    /// Called every time a key gets pressed
+    ///
+    /// - Up / Down or Left / Right: previous / next move
+    /// - Home / End: first / last move
+    /// - E: save the report in the data directory
+    /// - Escape: back to the game
+    fn key_down(&mut self, _ctx: &mut Context, _world: &mut World, keycode: KeyCode, _keymod: KeyMods) -> Transition {
+        let index = self.index as isize;
+        match keycode {
+            KeyCode::Escape => {info!("LEAVE the analysis from key Escape"); return Transition::Pop;},
+            KeyCode::Up | KeyCode::Left => self.select(index - 1),
+            KeyCode::Down | KeyCode::Right => self.select(index + 1),
+            KeyCode::Home => self.select(0),
+            KeyCode::End => self.select(isize::MAX),
+            KeyCode::E => self.export(),
+            _ => {}
+        }
+        Transition::None
+    }
+}
+
+#[allow(unreachable_code)]
+#[cfg(test)]
+mod tests {
+    use crate::*;
+
+    fn analysis(position: Option<&str>, moves: &str) -> Analysis {
+        let game = SavedGame {
+            moves: String::from(moves),
+            position: position.map(String::from),
+            ..SavedGame::default()
+        };
+        Analysis::new(&game, Engine::new(2)).unwrap()
+    }
+
+    #[test]
+    fn blunder() {
+        // Black can take the corner a1 but play b2
+        let analysis = analysis(Some(&format!("-OOX------OX----{} X", "-".repeat(48))), "b2");
+        let report = analysis.moves()[0];
+        assert_eq!(report.piece, Piece::BLACK);
+        assert_eq!(report.best, GridPosition::new(0, 0));
+        assert!(report.best_score > report.score);
+        assert!(report.is_blunder());
+        assert_eq!(analysis.blunders(Piece::BLACK), 1);
+        assert_eq!(analysis.total_error(Piece::BLACK), report.error());
+        assert_eq!(analysis.total_error(Piece::WHITE), 0);
+    }
+
+    #[test]
+    fn best_moves() {
+        let analysis = analysis(None, "f5d6c3");
+        assert_eq!(analysis.moves().len(), 3);
+        assert_eq!(analysis.moves()[1].piece, Piece::WHITE);
+        assert_eq!(analysis.board_before(0), &Board::default());
+        assert_eq!(analysis.board_before(10).score(Piece::BLACK), 5);
+        for report in analysis.moves() {
+            assert!(report.error() >= 0);
+            assert!(report.best_score >= report.score);
+        }
+        assert_eq!(analysis.evaluations()[1], -analysis.moves()[1].score);
+    }
+
+    #[test]
+    fn reports() {
+        let analysis = analysis(None, "f5d6");
+        let text = analysis.to_text();
+        assert!(text.starts_with("Analysis at depth 2"));
+        assert!(text.contains("  1 BLACK   f5"));
+        assert!(text.contains("Marie Curie (WHITE): error "));
+
+        let json: serde_json::Value = serde_json::from_str(&analysis.to_json()).unwrap();
+        assert_eq!(json["depth"], 2);
+        assert_eq!(json["moves"][1]["move"], "d6");
+        assert_eq!(json["moves"][1]["player"], "WHITE");
+        assert_eq!(json["black"]["name"], "Albert Einstein (BLACK)");
+    }
+
+    #[test]
+    fn view() {
+        let analysis = analysis(None, "f5d6c3d3c4");
+        let mut view = AnalysisView::new(analysis, Theme::default());
+        view.select(-1);
+        assert_eq!(view.index, 0);
+        view.select(42);
+        assert_eq!(view.index, 4);
+        let text = view.panel_text(2);
+        assert!(text.contains(">  5."));
+        assert!(!text.contains("  3."));
+    }
+}
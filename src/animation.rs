@@ -0,0 +1,107 @@
+//! Animation of the last played move
+
+use std::time::Duration;
+
+use crate::*;
+
+/// The last played move and its animation
+///
+/// The eaten pieces shrink with their old color then grow with the new one,
+/// and the played piece fade in. When the animation is over the move stay marked.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MoveAnimation {
+    position: GridPosition,
+    piece: Piece,
+    flips: Flips,
+    elapsed: Duration,
+    /// Duration of the animation, not animated if `None`
+    duration: Option<Duration>,
+}
+
+impl MoveAnimation {
+    /// Start the animation of a move
+    pub fn new(position: GridPosition, piece: Piece, flips: Flips, duration: Option<Duration>) -> Self {
+        MoveAnimation { position, piece, flips, elapsed: Duration::ZERO, duration }
+    }
+
+    /// Return the position of the move
+    pub fn position(&self) -> GridPosition {
+        self.position
+    }
+
+    /// Advance the animation, `delta` is the time elapsed since the last update
+    pub fn update(&mut self, delta: Duration) {
+        self.elapsed += delta;
+    }
+
+    /// Verify if the animation is not over
+    pub fn is_running(&self) -> bool {
+        self.progress() < 1.0
+    }
+
+    /// Return the progress of the animation, from 0 (start) to 1 (end)
+    pub fn progress(&self) -> f32 {
+        match self.duration {
+            Some(duration) if !duration.is_zero() => {
+                (self.elapsed.as_secs_f32() / duration.as_secs_f32()).min(1.0)
+            }
+            _ => 1.0,
+        }
+    }
+
+    /// Return how to draw the piece at this position: (piece, scale, opacity)
+    ///
+    /// `None` if the piece is not animated.
+    pub fn disc(&self, position: GridPosition) -> Option<(Piece, f32, f32)> {
+        if !self.is_running() {
+            return None;
+        }
+        let progress = self.progress();
+        if position == self.position {
+            Some((self.piece, 1.0, progress))
+        } else if self.flips.contains(position) {
+            if progress < 0.5 {
+                Some((self.piece.next(), 1.0 - 2.0 * progress, 1.0))
+            } else {
+                Some((self.piece, 2.0 * progress - 1.0, 1.0))
+            }
+        } else {
+            None
+        }
+    }
+}
+
+#[allow(unreachable_code)]
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use crate::*;
+
+    #[test]
+    fn animate() {
+        let mut board = Board::default();
+        let position = GridPosition::new(5, 4);
+        let flips = board.update(position, Piece::BLACK);
+        let mut animation = MoveAnimation::new(position, Piece::BLACK, flips, Some(Duration::from_millis(400)));
+        assert!(animation.is_running());
+        assert_eq!(animation.disc(position), Some((Piece::BLACK, 1.0, 0.0)));
+        assert_eq!(animation.disc(GridPosition::new(4, 4)), Some((Piece::WHITE, 1.0, 1.0)));
+        assert_eq!(animation.disc(GridPosition::new(3, 3)), None);
+
+        animation.update(Duration::from_millis(300));
+        assert_eq!(animation.progress(), 0.75);
+        assert_eq!(animation.disc(GridPosition::new(4, 4)), Some((Piece::BLACK, 0.5, 1.0)));
+
+        animation.update(Duration::from_millis(200));
+        assert!(!animation.is_running());
+        assert_eq!(animation.disc(position), None);
+    }
+
+    #[test]
+    fn disabled() {
+        let animation = MoveAnimation::new(GridPosition::new(5, 4), Piece::BLACK, Flips::default(), None);
+        assert!(!animation.is_running());
+        assert_eq!(animation.position(), GridPosition::new(5, 4));
+    }
+}
@@ -0,0 +1,202 @@
+//! The application of the window: a stack of scenes
+
+use ggez::{Context, event, GameResult};
+use ggez::event::{KeyCode, KeyMods, MouseButton};
+use ggez::graphics::{self, Rect};
+use log::{error, info};
+
+use crate::*;
+
+/// State of the window: the stacked scenes and their shared data
+///
+/// The main menu is always at the bottom of the stack.
+pub struct App {
+    world: World,
+    scenes: Vec<Box<dyn Scene>>,
+}
+
+impl App {
+    /// Create the application, the main menu is shown
+    pub fn new(config: Config, theme: Theme) -> Self {
+        App {
+            world: World::new(config, theme),
+            scenes: vec![Box::new(MainMenu::new())],
+        }
+    }
+
+    /// Start on a game instead of the main menu
+    pub fn with_game(self, game: Othello) -> Self {
+        self.with_scene(Box::new(game))
+    }
+
+    /// Start on a replay instead of the main menu
+    pub fn with_replay(self, replay: Replay) -> Self {
+        self.with_scene(Box::new(replay))
+    }
+
+    /// Show a scene over the main menu
+    pub fn with_scene(mut self, scene: Box<dyn Scene>) -> Self {
+        self.scenes.push(scene);
+        self
+    }
+
+    /// Use the whole drawable area of the window as the screen (in physical pixel)
+    ///
+    /// On a high DPI screen the window have more pixels than its size: the board and the texts are bigger.
+    pub fn fit_screen(ctx: &mut Context) -> GameResult {
+        let (width, height) = graphics::drawable_size(ctx);
+        graphics::set_screen_coordinates(ctx, Rect::new(0.0, 0.0, width, height))
+    }
+
+    /// Return the number of stacked scenes
+    pub fn len(&self) -> usize {
+        self.scenes.len()
+    }
+
+    /// Verify if there is no scene (the window must be closed)
+    pub fn is_empty(&self) -> bool {
+        self.scenes.is_empty()
+    }
+
+    /// Apply what the top scene asked, return `false` if the window must be closed
+    pub fn apply(&mut self, transition: Transition) -> bool {
+        match transition {
+            Transition::None => {}
+            Transition::Push(scene) => self.scenes.push(scene),
+            Transition::Pop => {
+                if let Some(mut scene) = self.scenes.pop() {
+                    scene.leave(&mut self.world);
+                }
+            }
+            Transition::Replace(scene) => {
+                if let Some(mut old_scene) = self.scenes.pop() {
+                    old_scene.leave(&mut self.world);
+                }
+                self.scenes.push(scene);
+            }
+            Transition::Quit => self.leave_all(),
+        }
+        !self.scenes.is_empty()
+    }
+
+    /// Remove every scene, from the top to the bottom
+    fn leave_all(&mut self) {
+        while let Some(mut scene) = self.scenes.pop() {
+            scene.leave(&mut self.world);
+        }
+    }
+
+    /// Apply a transition and close the window if it is needed
+    fn apply_or_quit(&mut self, ctx: &mut Context, transition: Transition) {
+        if !self.apply(transition) {
+            info!("EXIT: no more scene");
+            event::quit(ctx);
+        }
+    }
+}
+
+impl event::EventHandler<ggez::GameError> for App {
+    /// Update the scene on the top of the stack
+    fn update(&mut self, ctx: &mut Context) -> GameResult {
+        if let Some(scene) = self.scenes.last_mut() {
+            let transition = scene.update(ctx, &mut self.world)?;
+            self.apply_or_quit(ctx, transition);
+        }
+        Ok(())
+    }
+
+    /// Render the scene on the top of the stack, and the scenes under it if it is a popup
+    fn draw(&mut self, ctx: &mut Context) -> GameResult {
+        let first = self.scenes.iter().rposition(|scene| !scene.is_popup()).unwrap_or(0);
+        for scene in self.scenes[first..].iter_mut() {
+            scene.draw(ctx, &self.world)?;
+        }
+        graphics::present(ctx)
+    }
+
+    /// Called every time a mouse button gets pressed
+    fn mouse_button_down_event(&mut self, ctx: &mut Context, button: MouseButton, x: f32, y: f32) {
+        if let Some(scene) = self.scenes.last_mut() {
+            let transition = scene.mouse_down(ctx, &mut self.world, button, x, y);
+            self.apply_or_quit(ctx, transition);
+        }
+    }
+
+    /// Called every time a key gets pressed
+    fn key_down_event(&mut self, ctx: &mut Context, keycode: KeyCode, keymod: KeyMods, _repeat: bool) {
+        if let Some(scene) = self.scenes.last_mut() {
+            let transition = scene.key_down(ctx, &mut self.world, keycode, keymod);
+            self.apply_or_quit(ctx, transition);
+        }
+    }
+
+    /// Called when the window is resized, the scenes compute their layout from the new screen
+    fn resize_event(&mut self, ctx: &mut Context, width: f32, height: f32) {
+        info!("RESIZE the window to {}x{}", width, height);
+        if let Err(err) = graphics::set_screen_coordinates(ctx, Rect::new(0.0, 0.0, width, height)) {
+            error!("Cannot resize the screen: {}", err);
+        }
+    }
+
+    /// Called when the user close the window, every scene is left (the running game is saved)
+    fn quit_event(&mut self, _ctx: &mut Context) -> bool {
+        self.leave_all();
+        false
+    }
+}
+
+#[allow(unreachable_code)]
+#[cfg(test)]
+mod tests {
+    use std::cell::Cell;
+    use std::rc::Rc;
+
+    use ggez::{Context, GameResult};
+
+    use crate::*;
+
+    /// A scene that count how many times it is left
+    struct Counter(Rc<Cell<u8>>);
+
+    impl Scene for Counter {
+        fn draw(&mut self, _ctx: &mut Context, _world: &World) -> GameResult {
+            Ok(())
+        }
+
+        fn leave(&mut self, _world: &mut World) {
+            self.0.set(self.0.get() + 1);
+        }
+    }
+
+    #[test]
+    fn stack() {
+        let left = Rc::new(Cell::new(0));
+        let mut app = App::new(Config::default(), Theme::default())
+            .with_scene(Box::new(Counter(left.clone())));
+        assert_eq!(app.len(), 2);
+
+        assert!(app.apply(Transition::Push(Box::new(Popup::new("Hello")))));
+        assert_eq!(app.len(), 3);
+        assert!(app.apply(Transition::Pop));
+        assert_eq!(left.get(), 0);
+
+        assert!(app.apply(Transition::Replace(Box::new(Counter(left.clone())))));
+        assert_eq!((app.len(), left.get()), (2, 1));
+        assert!(app.apply(Transition::Pop));
+        assert_eq!((app.len(), left.get()), (1, 2));
+
+        // Leaving the main menu close the window
+        assert!(!app.apply(Transition::Pop));
+        assert!(app.is_empty());
+    }
+
+    #[test]
+    fn quit() {
+        let left = Rc::new(Cell::new(0));
+        let mut app = App::new(Config::default(), Theme::default())
+            .with_scene(Box::new(Counter(left.clone())))
+            .with_scene(Box::new(Counter(left.clone())));
+        assert!(!app.apply(Transition::Quit));
+        assert_eq!(left.get(), 2);
+    }
+}
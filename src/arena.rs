@@ -0,0 +1,167 @@
+//! Matches between two engines without window, to compare them
+//!
+//! Each start is played twice with the colors swapped, so a start that favor a color
+//! does not favor an engine.
+
+use std::fmt;
+use std::io::{self, Write};
+
+use fastrand::Rng;
+
+use crate::*;
+
+/// A match between two engines
+#[derive(Debug, Clone)]
+pub struct Arena {
+    engines: [Engine; 2],
+    start: StartPosition,
+    board_size: (i16, i16),
+    rng: Rng,
+}
+
+/// Result of a match, from the point of view of the first engine
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct ArenaResult {
+    pub wins: u32,
+    pub draws: u32,
+    pub losses: u32,
+    /// Sum of the final disc differences (the discs of the first engine minus the other ones)
+    pub disc_difference: i32,
+}
+
+impl Arena {
+    /// Create a match from the standard start on a 8x8 board
+    pub fn new(first: Engine, second: Engine) -> Self {
+        Arena { engines: [first, second], start: StartPosition::Standard, board_size: DEFAULT_GRID_SIZE, rng: Rng::new() }
+    }
+
+    /// Set the start of the games
+    pub fn set_start(mut self, start: StartPosition) -> Self {
+        self.start = start;
+        self
+    }
+
+    /// Set the size of the board
+    pub fn set_board_size(mut self, board_size: (i16, i16)) -> Self {
+        self.board_size = board_size;
+        self
+    }
+
+    /// Choose the random starts from a seed, the same seed give the same match
+    pub fn set_seed(mut self, seed: u64) -> Self {
+        self.rng = Rng::with_seed(seed);
+        self
+    }
+
+    /// Play a game from a start, return the final board
+    pub fn play_game(&self, start: &SavedGame, first_is_black: bool) -> Result<Board, NotationError> {
+        let (mut board, mut turn) = start.replay()?;
+        loop {
+            if !board.can_play(turn) {
+                turn = turn.next();
+                if !board.can_play(turn) {
+                    return Ok(board);
+                }
+            }
+            let engine = if (turn == Piece::BLACK) == first_is_black { self.engines[0] } else { self.engines[1] };
+            let position = engine.best_move(&board, turn).expect("The player can play");
+            board.update(position, turn);
+            turn = turn.next();
+        }
+    }
+
+    /// Play `starts` starts twice (the first engine is black then white)
+    ///
+    /// A line per game is written in `output`, e.g. "1. WHITE f5d6: BLACK win 40-24" where
+    /// WHITE is the color of the first engine, then the result.
+    pub fn run<W: Write>(&mut self, starts: usize, mut output: W) -> io::Result<ArenaResult> {
+        let mut result = ArenaResult::default();
+        let game = SavedGame { board: BoardConfig { width: self.board_size.0, height: self.board_size.1 }, ..SavedGame::default() };
+        for index in 0..starts {
+            let start = self.start.apply(game.clone(), &mut self.rng);
+            for first_is_black in [true, false] {
+                let board = self.play_game(&start, first_is_black)
+                    .map_err(|err| io::Error::new(io::ErrorKind::InvalidData, err))?;
+                let first_piece = if first_is_black { Piece::BLACK } else { Piece::WHITE };
+                let difference = board.score(first_piece) as i32 - board.score(first_piece.next()) as i32;
+                result.record(difference);
+                writeln!(output, "{}. {} {}: {}",
+                    index + 1, first_piece, if start.moves.is_empty() { "-" } else { &start.moves },
+                    Outcome::new(&board, EndReason::NoMoreMoves))?;
+            }
+        }
+        writeln!(output, "{}", result)?;
+        Ok(result)
+    }
+}
+
+impl ArenaResult {
+    /// Count a game with its final disc difference
+    pub fn record(&mut self, difference: i32) {
+        match difference {
+            0 => self.draws += 1,
+            difference if difference > 0 => self.wins += 1,
+            _ => self.losses += 1,
+        }
+        self.disc_difference += difference;
+    }
+
+    /// Return the number of games
+    pub fn games(&self) -> u32 {
+        self.wins + self.draws + self.losses
+    }
+
+    /// Return the score of the first engine (from 0 to 1), a draw count as half a win
+    pub fn score(&self) -> f32 {
+        if self.games() == 0 { 0.5 } else { (self.wins as f32 + 0.5 * self.draws as f32) / self.games() as f32 }
+    }
+}
+
+impl fmt::Display for ArenaResult {
+    /// Write the result, e.g. "First engine: 3 wins, 0 draws, 2 losses (60%), average disc difference +1.5"
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let average = if self.games() == 0 { 0.0 } else { self.disc_difference as f32 / self.games() as f32 };
+        write!(f, "First engine: {} wins, {} draws, {} losses ({:.0}%), average disc difference {:+.1}",
+            self.wins, self.draws, self.losses, 100.0 * self.score(), average)
+    }
+}
+
+#[allow(unreachable_code)]
+#[cfg(test)]
+mod tests {
+    use std::io;
+
+    use crate::*;
+
+    #[test]
+    fn result() {
+        let mut result = ArenaResult::default();
+        result.record(10);
+        result.record(0);
+        result.record(-4);
+        result.record(6);
+        assert_eq!(result.games(), 4);
+        assert_eq!(result.to_string(), "First engine: 2 wins, 1 draws, 1 losses (62%), average disc difference +3.0");
+    }
+
+    #[test]
+    fn run() {
+        let mut arena = Arena::new(Engine::new(2), Engine::new(1))
+            .set_start(StartPosition::Random(2))
+            .set_board_size((6, 6))
+            .set_seed(1);
+        let mut output = Vec::new();
+        let result = arena.run(2, &mut output).unwrap();
+        assert_eq!(result.games(), 4);
+        let output = String::from_utf8(output).unwrap();
+        assert_eq!(output.lines().count(), 5);
+        assert!(output.starts_with("1. BLACK "));
+
+        // The same seed play the same games
+        let mut arena = Arena::new(Engine::new(2), Engine::new(1))
+            .set_start(StartPosition::Random(2))
+            .set_board_size((6, 6))
+            .set_seed(1);
+        assert_eq!(arena.run(2, io::sink()).unwrap(), result);
+    }
+}
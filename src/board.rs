@@ -1,11 +1,14 @@
 //! The board module that manage all interaction with the board
 
 // std crates
-use std::cmp::{max, min};
+use std::cell::RefCell;
+use std::cmp::max;
+use std::collections::HashMap;
 use std::fmt;
 
 // extern crates
 use ggez::{graphics, Context, GameResult};
+use ggez::graphics::Rect;
 use glam::Vec2;
 use grid::Grid;
 use log::{debug, error};
@@ -15,10 +18,11 @@ use crate::*;
 
 
 /// Describe a case of the Board
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
 pub enum Piece {
     BLACK,
     WHITE,
+    #[default]
     EMPTY,
 }
 
@@ -41,38 +45,48 @@ impl Piece {
 
     /// Draw a Piece of the Board at a certain point
     ///
-    /// - Piece::BLACK => Render by a `black` circle of 80% of the GRID_CELL_SIZE
-    /// - Piece::WHITE => Render by a `white` circle of 80% of the GRID_CELL_SIZE
+    /// - Piece::BLACK => Render by a `black` circle of 80% of the cell size
+    /// - Piece::WHITE => Render by a `white` circle of 80% of the cell size
     /// - Piece::EMPTY => Not drawn
-    fn draw<P>(&self, ctx: &mut Context, theme: Theme, point: P) -> GameResult
+    ///
+    /// The circle is resized by `scale` and made transparent by `alpha` (both from 0 to 1),
+    /// it is outlined or shaded according to the [`PieceStyle`] of the theme
+    fn draw<P>(&self, ctx: &mut Context, theme: &Theme, point: P, cell_size: (f32, f32), scale: f32, alpha: f32) -> GameResult
     where
         P: Into<mint::Point2<f32>>,
     {
-        let color = match self {
+        let mut color = match self {
             Piece::BLACK => theme.piece_colors.0,
             Piece::WHITE => theme.piece_colors.1,
             Piece::EMPTY => return Ok(()),
         };
-        let mesh = graphics::MeshBuilder::new()
-            .circle(
-                graphics::DrawMode::fill(),
-                point,
-                2.0 * min(GRID_CELL_SIZE.0, GRID_CELL_SIZE.1) as f32 / 5.0,
-                1.0,
-                color,
-            )?
-            .build(ctx)?;
+        let radius = scale * 2.0 * cell_size.0.min(cell_size.1) / 5.0;
+        if radius < 1.0 || alpha <= 0.0 {
+            return Ok(());
+        }
+        color.a *= alpha;
+        let point: mint::Point2<f32> = point.into();
+        let mut builder = graphics::MeshBuilder::new();
+        builder.circle(graphics::DrawMode::fill(), point, radius, 1.0, color)?;
+        match theme.piece_style {
+            PieceStyle::Flat => {}
+            PieceStyle::Outline => {
+                let mut border = theme.grid_color;
+                border.a *= alpha;
+                builder.circle(graphics::DrawMode::stroke(radius / 8.0), point, radius, 1.0, border)?;
+            }
+            PieceStyle::Shaded => {
+                let reflection = graphics::Color::new(1.0, 1.0, 1.0, 0.3 * alpha);
+                let center = Vec2::new(point.x - radius / 3.0, point.y - radius / 3.0);
+                builder.circle(graphics::DrawMode::fill(), center, radius / 3.0, 1.0, reflection)?;
+            }
+        }
+        let mesh = builder.build(ctx)?;
         graphics::draw(ctx, &mesh, graphics::DrawParam::default())?;
         Ok(())
     }
 }
 
-impl Default for Piece {
-    fn default() -> Self {
-        Piece::EMPTY
-    }
-}
-
 impl fmt::Display for Piece {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         match self {
@@ -83,38 +97,130 @@ impl fmt::Display for Piece {
     }
 }
 
-#[derive(Debug, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq, Eq)]
 pub struct Board {
     board: Grid<Piece>,
     //historic: Vec<GridPosition>,
-    //theme: Theme,
+    //theme: &Theme,
+}
+
+/// Pieces eaten by a move
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Flips {
+    /// The eaten positions, direction by direction from the played position
+    pub positions: Vec<GridPosition>,
+    /// Number of eaten pieces in each direction (only the directions that eat)
+    pub directions: Vec<(GridPosition, u8)>,
+}
+
+impl Flips {
+    /// Return the number of eaten pieces
+    pub fn len(&self) -> usize {
+        self.positions.len()
+    }
+
+    /// Verify if no piece is eaten
+    pub fn is_empty(&self) -> bool {
+        self.positions.is_empty()
+    }
+
+    /// Verify if the piece at this position is eaten
+    pub fn contains(&self, position: GridPosition) -> bool {
+        self.positions.contains(&position)
+    }
+
+    /// Return the number of eaten pieces in a direction
+    pub fn count(&self, direction: GridPosition) -> u8 {
+        self.directions.iter()
+            .find(|(d, _)| *d == direction)
+            .map_or(0, |(_, count)| *count)
+    }
+
+    /// Return the eaten cases as a mask of the board, row by row
+    pub fn mask(&self, size: (i16, i16)) -> Vec<bool> {
+        let mut mask = vec![false; (size.0 * size.1) as usize];
+        for position in &self.positions {
+            mask[(position.y * size.0 + position.x) as usize] = true;
+        }
+        mask
+    }
 }
 
 impl Board {
-    /// Create a new Board
-    pub fn new() -> Self {
+    /// Create a new empty Board of `size` cells (columns, rows)
+    ///
+    /// # Panics
+    ///
+    /// Panics if the size is not valid (see [`Board::is_valid_size`])
+    pub fn new(size: (i16, i16)) -> Self {
+        assert!(Board::is_valid_size(size), "Invalid board size: {}x{}", size.0, size.1);
         Board {
-            board: Grid::new(GRID_SIZE.1 as usize, GRID_SIZE.0 as usize),
+            board: Grid::new(size.1 as usize, size.0 as usize),
+        }
+    }
+
+    /// Create a Board of `size` cells ready for a new game
+    pub fn with_size(size: (i16, i16)) -> Self {
+        let mut board = Board::new(size);
+        board.init();
+        board
+    }
+
+    /// Verify if a board can be created with this size
+    ///
+    /// Both dimensions must be even (so the 4 starting pieces are in the middle),
+    /// at least 4 and at most [`MAX_GRID_SIZE`] (the columns are named from `a` to `z`).
+    pub fn is_valid_size(size: (i16, i16)) -> bool {
+        let valid = |length: i16| (4..=MAX_GRID_SIZE).contains(&length) && length % 2 == 0;
+        valid(size.0) && valid(size.1)
+    }
+
+    /// Return the size of the board (columns, rows)
+    pub fn size(&self) -> (i16, i16) {
+        (self.board.cols() as i16, self.board.rows() as i16)
+    }
+
+    /// Compute the size of a cell (in pixel) when the board fill the screen
+    pub fn cell_size(&self, screen_size: (f32, f32)) -> (f32, f32) {
+        let (cols, rows) = self.size();
+        (screen_size.0 / cols as f32, screen_size.1 / rows as f32)
+    }
+
+    /// Return the star points: the corners of the cells that are 2 cells away from the sides
+    ///
+    /// On a 8x8 board these are the four traditional points around the center (top left corner of c3, g3, c7 and g7).
+    /// A board smaller than 6x6 have no star points.
+    pub fn star_points(&self) -> Vec<GridPosition> {
+        let (width, height) = self.size();
+        if width < 6 || height < 6 {
+            return Vec::new();
         }
+        vec![
+            GridPosition::new(2, 2),
+            GridPosition::new(width - 2, 2),
+            GridPosition::new(2, height - 2),
+            GridPosition::new(width - 2, height - 2),
+        ]
     }
 
     /// Initialize the board for a new game
     ///
     /// Set the 4 case in the middle of the board
     pub fn init(&mut self) {
-        let mid_x = self.board.cols() / 2 - 1;
-        let mid_y = self.board.rows() / 2 - 1;
-        self.board[mid_x][mid_y + 1] = Piece::BLACK;
-        self.board[mid_x + 1][mid_y] = Piece::BLACK;
-        self.board[mid_x][mid_y] = Piece::WHITE;
-        self.board[mid_x + 1][mid_y + 1] = Piece::WHITE;
+        let mid_x = self.board.cols() as i16 / 2 - 1;
+        let mid_y = self.board.rows() as i16 / 2 - 1;
+        self.set(GridPosition::new(mid_x + 1, mid_y), Piece::BLACK);
+        self.set(GridPosition::new(mid_x, mid_y + 1), Piece::BLACK);
+        self.set(GridPosition::new(mid_x, mid_y), Piece::WHITE);
+        self.set(GridPosition::new(mid_x + 1, mid_y + 1), Piece::WHITE);
     }
 
     /// Reset the content of the board
     /// i.e. clear then init the board
     pub fn reset(&mut self) {
-        for y in 0..GRID_SIZE.1 {
-            for x in 0..GRID_SIZE.0 {
+        let (width, height) = self.size();
+        for y in 0..height {
+            for x in 0..width {
                 self.set(GridPosition::new(x, y), Piece::default())
             }
         }
@@ -146,11 +252,28 @@ impl Board {
     }
 
     /// Get a specific case of the board
+    ///
+    /// # Panics
+    ///
+    /// Panics if the position is out of the board (see [`Board::try_get`])
     pub fn get(&self, position: GridPosition) -> Piece {
         self.board[position.y as usize][position.x as usize]
     }
 
+    /// Get a specific case of the board, or an error if the position is out of the board
+    pub fn try_get(&self, position: GridPosition) -> Result<Piece, OthelloError> {
+        if self.in_board(position) {
+            Ok(self.get(position))
+        } else {
+            Err(OthelloError::OutOfBounds(position))
+        }
+    }
+
     /// Set a specific case of the board
+    ///
+    /// # Panics
+    ///
+    /// Panics if the position is out of the board
     pub fn set(&mut self, position: GridPosition, piece: Piece) {
         self.board[position.y as usize][position.x as usize] = piece;
     }
@@ -159,7 +282,8 @@ impl Board {
     fn get_line(&self, start: GridPosition, direction: GridPosition) -> Vec<GridPosition> {
         let last_position = self.get_end_line(start, direction);
         let mut current_position = start;
-        let mut line = Vec::with_capacity(max(GRID_SIZE.0, GRID_SIZE.1) as usize);
+        let (width, height) = self.size();
+        let mut line = Vec::with_capacity(max(width, height) as usize);
         while current_position != last_position {
             current_position += direction;
             line.push(current_position);
@@ -181,8 +305,33 @@ impl Board {
         final_position - direction
     }
 
-    /// Update the board without any verification
-    pub fn update(&mut self, position: GridPosition, player_piece: Piece) {
+    /// Verify then play a move, return the eaten pieces
+    pub fn play(&mut self, position: GridPosition, player_piece: Piece) -> Result<Flips, OthelloError> {
+        if player_piece == Piece::EMPTY {
+            return Err(OthelloError::EmptyPiece);
+        }
+        if self.try_get(position)? != Piece::EMPTY {
+            return Err(OthelloError::Occupied(position));
+        }
+        if !self.is_valid_move(position, player_piece) {
+            return Err(OthelloError::IllegalMove { position, piece: player_piece });
+        }
+        Ok(self.update(position, player_piece))
+    }
+
+    /// Cancel a move played at `position` that eat the pieces `flips`
+    pub fn undo(&mut self, position: GridPosition, flips: &Flips) {
+        self.set(position, Piece::EMPTY);
+        for position in &flips.positions {
+            let piece = self.get(*position);
+            self.set(*position, piece.next());
+        }
+    }
+
+    /// Update the board without any verification (see [`Board::play`])
+    ///
+    /// Return the eaten pieces
+    pub fn update(&mut self, position: GridPosition, player_piece: Piece) -> Flips {
         let enemy_piece = player_piece.next();
         let mut directions_to_eat = Vec::new();
         let mut new_piece;
@@ -218,7 +367,7 @@ impl Board {
             }
         }
         self.set(position, player_piece);
-        self.eat_by_directions(position, directions_to_eat, player_piece);
+        self.eat_by_directions(position, directions_to_eat, player_piece)
     }
 
     /// eat lines using the eat_by_direction() method
@@ -227,27 +376,34 @@ impl Board {
         position: GridPosition,
         directions: Vec<GridPosition>,
         player_piece: Piece,
-    ) {
+    ) -> Flips {
+        let mut flips = Flips::default();
         for direction in directions.iter() {
-            self.eat_by_direction(position, *direction, player_piece);
+            let eaten = self.eat_by_direction(position, *direction, player_piece);
+            flips.directions.push((*direction, eaten.len() as u8));
+            flips.positions.extend(eaten);
         }
+        flips
     }
 
-    /// Eat a line (see the [rule](https://documentation.help/Reversi-Rules/rules.htm))
+    /// Eat a line (see the [rule](https://documentation.help/Reversi-Rules/rules.htm) and the [`crate::Tutorial`])
     fn eat_by_direction(
         &mut self,
         position: GridPosition,
         direction: GridPosition,
         player_piece: Piece,
-    ) {
+    ) -> Vec<GridPosition> {
         let mut new_piece;
+        let mut eaten = Vec::new();
         for current_position in self.get_line(position, direction).iter() {
             new_piece = self.get(*current_position);
             if new_piece == player_piece {
                 break;
             }
-            self.set(*current_position, new_piece.next())
+            self.set(*current_position, new_piece.next());
+            eaten.push(*current_position);
         }
+        eaten
     }
 
     /// Verify if this move is valid for a specific player
@@ -256,8 +412,10 @@ impl Board {
         let mut new_piece;
         let enemy_piece = player_piece.next();
 
-        // If the initial position is empty
-        if self.get(position) == Piece::EMPTY {
+        // If the initial position is in the board and empty
+        if !self.in_board(position) {
+            debug!("Position out of the board: {}", position);
+        } else if self.get(position) == Piece::EMPTY {
             // For all direction
             'outer: for direction in Board::get_directions() {
                 // If it is a possible direction
@@ -297,8 +455,9 @@ impl Board {
     /// Compute and return all valid moves for a player
     pub fn get_valid_moves(&self, player_piece: Piece) -> Vec<GridPosition> {
         let mut valid_moves = Vec::new();
-        for y in 0..GRID_SIZE.1 {
-            for x in 0..GRID_SIZE.0 {
+        let (width, height) = self.size();
+        for y in 0..height {
+            for x in 0..width {
                 if self.get(GridPosition::new(x, y)) == Piece::EMPTY {
                     let position = GridPosition::new(x, y);
                     if self.is_valid_move(position, player_piece) {
@@ -336,11 +495,11 @@ impl Board {
 
     /// Verify if a player can play with the actual board
     pub fn can_play(&self, player_piece: Piece) -> bool {
-        self.get_valid_moves(player_piece).len() > 0
+        !self.get_valid_moves(player_piece).is_empty()
     }
 
     /// Verify if a position is in the board
-    fn in_board(&self, position: GridPosition) -> bool {
+    pub fn in_board(&self, position: GridPosition) -> bool {
         0 <= position.x
             && position.x < self.board.cols() as i16
             && 0 <= position.y
@@ -354,8 +513,7 @@ impl Board {
 
     /// Verify if the board is full (no Piece::EMPTY)
     pub fn is_full(&self) -> bool {
-        let height: i16 = self.board.rows() as i16;
-        let width: i16 = self.board.cols() as i16;
+        let (width, height) = self.size();
         for y in 0..height {
             for x in 0..width {
                 if self.is_empty(GridPosition::new(x, y)) {
@@ -363,14 +521,15 @@ impl Board {
                 }
             }
         }
-        return true;
+        true
     }
 
     /// Compute the score of a player
-    pub fn score(&self, player_piece: Piece) -> u8 {
+    pub fn score(&self, player_piece: Piece) -> u16 {
         let mut score = 0;
-        for y in 0..GRID_SIZE.1 {
-            for x in 0..GRID_SIZE.0 {
+        let (width, height) = self.size();
+        for y in 0..height {
+            for x in 0..width {
                 if self.get(GridPosition::new(x, y)) == player_piece {
                     score += 1;
                 }
@@ -380,26 +539,56 @@ impl Board {
     }
 
     /// Draw all the board (grid + pieces + valid moves)
-    pub fn draw(&self, ctx: &mut Context, player_piece: Piece, theme: Theme) -> GameResult {
-        Board::draw_empty_board(ctx, theme)?;
-        self.draw_content_board(ctx, theme)?;
-        if theme.valid_moves_color.is_some() {
-            self.draw_valid_move(ctx, theme, player_piece)?;
+    ///
+    /// The board fill the whole screen, each cell size is computed from the board size.
+    pub fn draw(&self, ctx: &mut Context, player_piece: Piece, theme: &Theme) -> GameResult {
+        self.draw_animated(ctx, graphics::screen_coordinates(ctx), player_piece, theme, None)
+    }
+
+    /// Draw the board in the area `area` (in pixel) with the last move animated then marked
+    ///
+    /// The valid moves are drawn when the animation is over.
+    /// The coordinates are drawn around the grid, see [`Theme::grid_area`].
+    pub fn draw_animated(
+        &self,
+        ctx: &mut Context,
+        area: Rect,
+        player_piece: Piece,
+        theme: &Theme,
+        last_move: Option<&MoveAnimation>,
+    ) -> GameResult {
+        if let Some(color) = theme.coordinates_color {
+            self.draw_coordinates(ctx, theme, color, area)?;
+        }
+        let area = theme.grid_area(area);
+        self.draw_empty_board(ctx, theme, area)?;
+        self.draw_content_board(ctx, theme, area, last_move)?;
+        let is_animated = last_move.is_some_and(MoveAnimation::is_running);
+        if let (Some(last_move), Some(color), false) = (last_move, theme.last_move_color, is_animated) {
+            self.draw_last_move(ctx, theme, color, last_move.position(), area)?;
+        }
+        if theme.valid_moves_color.is_some() && !is_animated {
+            self.draw_valid_move(ctx, theme, player_piece, area)?;
         }
         Ok(())
     }
 
-    /// Draw an empty board (only the grid)
-    fn draw_empty_board(ctx: &mut Context, theme: Theme) -> GameResult {
+    /// Draw an empty board (the texture and the grid)
+    fn draw_empty_board(&self, ctx: &mut Context, theme: &Theme, area: Rect) -> GameResult {
+        if let Some(path) = &theme.board_texture {
+            draw_texture(ctx, path, area)?;
+        }
+        let (width, height) = self.size();
+        let cell_size = self.cell_size((area.w, area.h));
         // horizontal line
-        for y in 1..GRID_SIZE.1 {
+        for y in 1..height {
             let mesh = graphics::MeshBuilder::new()
                 .line(
                     &[
-                        Vec2::new(0.0, (y * GRID_CELL_SIZE.1) as f32),
+                        Vec2::new(area.x, area.y + y as f32 * cell_size.1),
                         Vec2::new(
-                            (GRID_SIZE.0 * GRID_CELL_SIZE.0) as f32,
-                            (y * GRID_CELL_SIZE.1) as f32,
+                            area.x + width as f32 * cell_size.0,
+                            area.y + y as f32 * cell_size.1,
                         ),
                     ],
                     2.0,
@@ -409,14 +598,14 @@ impl Board {
             graphics::draw(ctx, &mesh, graphics::DrawParam::default())?;
         }
         // vertical line
-        for x in 1..GRID_SIZE.0 {
+        for x in 1..width {
             let mesh = graphics::MeshBuilder::new()
                 .line(
                     &[
-                        Vec2::new((x * GRID_CELL_SIZE.0) as f32, 0.0),
+                        Vec2::new(area.x + x as f32 * cell_size.0, area.y),
                         Vec2::new(
-                            (x * GRID_CELL_SIZE.0) as f32,
-                            (GRID_SIZE.1 * GRID_CELL_SIZE.1) as f32,
+                            area.x + x as f32 * cell_size.0,
+                            area.y + height as f32 * cell_size.1,
                         ),
                     ],
                     2.0,
@@ -425,54 +614,204 @@ impl Board {
                 .build(ctx)?;
             graphics::draw(ctx, &mesh, graphics::DrawParam::default())?;
         }
+        if let Some(color) = theme.star_points_color {
+            self.draw_star_points(ctx, color, area)?;
+        }
         Ok(())
     }
 
-    /// Draw the content of the board (only pieces)
-    fn draw_content_board(&self, ctx: &mut Context, theme: Theme) -> GameResult {
-        for y in 0..GRID_SIZE.1 {
-            for x in 0..GRID_SIZE.0 {
-                if self.board[y as usize][x as usize] == Piece::EMPTY {
-                    continue;
-                }
-                let center = Vec2::new(
-                    (x * GRID_CELL_SIZE.0) as f32 + GRID_CELL_SIZE.0 as f32 / 2.0,
-                    (y * GRID_CELL_SIZE.1) as f32 + GRID_CELL_SIZE.1 as f32 / 2.0,
-                );
-                self.board[y as usize][x as usize].draw(ctx, theme, center)?;
-            }
+    /// Draw the labels of the columns (a, b, ...) and of the rows (1, 2, ...) around the grid
+    fn draw_coordinates(&self, ctx: &mut Context, theme: &Theme, color: graphics::Color, area: Rect) -> GameResult {
+        let grid = theme.grid_area(area);
+        let margin = grid.x - area.x;
+        let font = graphics::Font::new(ctx, theme.font_path.as_ref())?;
+        let (width, height) = self.size();
+        let labels = (0..width)
+            .flat_map(|x| {
+                let center = self.cell_center(GridPosition::new(x, 0), grid).x;
+                let label = column_label(x).to_string();
+                [
+                    (label.clone(), Vec2::new(center, area.y + margin / 2.0)),
+                    (label, Vec2::new(center, area.bottom() - margin / 2.0)),
+                ]
+            })
+            .chain((0..height).flat_map(|y| {
+                let center = self.cell_center(GridPosition::new(0, y), grid).y;
+                let label = row_label(y);
+                [
+                    (label.clone(), Vec2::new(area.x + margin / 2.0, center)),
+                    (label, Vec2::new(area.right() - margin / 2.0, center)),
+                ]
+            }));
+        for (label, center) in labels {
+            let text = graphics::Text::new((label, font, 0.6 * margin));
+            let dimensions = text.dimensions(ctx);
+            let point = center - Vec2::new(dimensions.w / 2.0, dimensions.h / 2.0);
+            graphics::draw(ctx, &text, (point, color))?;
         }
         Ok(())
     }
 
-    /// Draw all the valid move for the current player
-    fn draw_valid_move(&self, ctx: &mut Context, theme: Theme, player_piece: Piece) -> GameResult {
-        let mut center;
-        for position in self.get_valid_moves(player_piece).iter() {
-            center = Vec2::new(
-                (position.x * GRID_CELL_SIZE.0) as f32 + GRID_CELL_SIZE.0 as f32 / 2.0,
-                (position.y * GRID_CELL_SIZE.1) as f32 + GRID_CELL_SIZE.1 as f32 / 2.0,
-            );
+    /// Draw the star points on the intersections of the grid
+    fn draw_star_points(&self, ctx: &mut Context, color: graphics::Color, area: Rect) -> GameResult {
+        let cell_size = self.cell_size((area.w, area.h));
+        for point in self.star_points() {
             let mesh = graphics::MeshBuilder::new()
                 .circle(
                     graphics::DrawMode::fill(),
-                    center,
-                    3.0 * min(GRID_CELL_SIZE.0, GRID_CELL_SIZE.1) as f32 / 10.0,
+                    Vec2::new(area.x + point.x as f32 * cell_size.0, area.y + point.y as f32 * cell_size.1),
+                    cell_size.0.min(cell_size.1) / 12.0,
                     1.0,
-                    theme.valid_moves_color.unwrap(),
+                    color,
                 )?
                 .build(ctx)?;
             graphics::draw(ctx, &mesh, graphics::DrawParam::default())?;
         }
         Ok(())
     }
+
+    /// Draw the content of the board (only pieces)
+    fn draw_content_board(
+        &self,
+        ctx: &mut Context,
+        theme: &Theme,
+        area: Rect,
+        last_move: Option<&MoveAnimation>,
+    ) -> GameResult {
+        let (width, height) = self.size();
+        let cell_size = self.cell_size((area.w, area.h));
+        for y in 0..height {
+            for x in 0..width {
+                let position = GridPosition::new(x, y);
+                let (piece, scale, alpha) = last_move
+                    .and_then(|animation| animation.disc(position))
+                    .unwrap_or((self.get(position), 1.0, 1.0));
+                if piece == Piece::EMPTY {
+                    continue;
+                }
+                let center = self.cell_center(position, area);
+                piece.draw(ctx, theme, center, cell_size, scale, alpha)?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Draw a small mark on the last played move (a big one with large markers)
+    fn draw_last_move(&self, ctx: &mut Context, theme: &Theme, color: graphics::Color, position: GridPosition, area: Rect) -> GameResult {
+        let cell_size = self.cell_size((area.w, area.h));
+        let radius = if theme.large_markers { 4.0 } else { 10.0 };
+        let mesh = graphics::MeshBuilder::new()
+            .circle(
+                graphics::DrawMode::fill(),
+                self.cell_center(position, area),
+                cell_size.0.min(cell_size.1) / radius,
+                1.0,
+                color,
+            )?
+            .build(ctx)?;
+        graphics::draw(ctx, &mesh, graphics::DrawParam::default())
+    }
+
+    /// Draw all the valid move for the current player
+    ///
+    /// With large markers a thick ring is drawn around each valid move.
+    fn draw_valid_move(
+        &self,
+        ctx: &mut Context,
+        theme: &Theme,
+        player_piece: Piece,
+        area: Rect,
+    ) -> GameResult {
+        let cell_size = self.cell_size((area.w, area.h));
+        let side = cell_size.0.min(cell_size.1);
+        let color = theme.valid_moves_color.unwrap();
+        for position in self.get_valid_moves(player_piece).iter() {
+            let center = self.cell_center(*position, area);
+            let mut builder = graphics::MeshBuilder::new();
+            builder.circle(graphics::DrawMode::fill(), center, 3.0 * side / 10.0, 1.0, color)?;
+            if theme.large_markers {
+                builder.circle(graphics::DrawMode::stroke(side / 15.0), center, 4.2 * side / 10.0, 1.0, color)?;
+            }
+            let mesh = builder.build(ctx)?;
+            graphics::draw(ctx, &mesh, graphics::DrawParam::default())?;
+        }
+        Ok(())
+    }
+
+    /// Draw the keyboard cursor: a frame around a cell, thicker with large markers
+    ///
+    /// `area` is the same area as in [`Board::draw_animated`].
+    pub fn draw_cursor(&self, ctx: &mut Context, theme: &Theme, position: GridPosition, area: Rect) -> GameResult {
+        let area = theme.grid_area(area);
+        let cell_size = self.cell_size((area.w, area.h));
+        let width = cell_size.0.min(cell_size.1) / if theme.large_markers { 8.0 } else { 20.0 };
+        let cell = Rect::new(
+            area.x + position.x as f32 * cell_size.0 + width / 2.0,
+            area.y + position.y as f32 * cell_size.1 + width / 2.0,
+            cell_size.0 - width,
+            cell_size.1 - width,
+        );
+        let mesh = graphics::MeshBuilder::new()
+            .rectangle(graphics::DrawMode::stroke(width), cell, theme.cursor_color)?
+            .build(ctx)?;
+        graphics::draw(ctx, &mesh, graphics::DrawParam::default())
+    }
+
+    /// Compute the center (in pixel) of a cell when the board is drawn in `area`
+    fn cell_center(&self, position: GridPosition, area: Rect) -> Vec2 {
+        let cell_size = self.cell_size((area.w, area.h));
+        Vec2::new(
+            area.x + position.x as f32 * cell_size.0 + cell_size.0 / 2.0,
+            area.y + position.y as f32 * cell_size.1 + cell_size.1 / 2.0,
+        )
+    }
+}
+
+thread_local! {
+    /// The textures already loaded, `None` if the image cannot be loaded
+    static TEXTURES: RefCell<HashMap<String, Option<graphics::Image>>> = RefCell::new(HashMap::new());
+}
+
+/// Draw an image of resources/ stretched to the area, the image is loaded only once
+fn draw_texture(ctx: &mut Context, path: &str, area: Rect) -> GameResult {
+    let image = TEXTURES.with(|textures| {
+        textures.borrow_mut()
+            .entry(path.to_string())
+            .or_insert_with(|| graphics::Image::new(ctx, path)
+                .map_err(|err| error!("Cannot load the texture {}: {}", path, err))
+                .ok())
+            .clone()
+    });
+    match image {
+        Some(image) => {
+            let scale = Vec2::new(area.w / image.width() as f32, area.h / image.height() as f32);
+            graphics::draw(ctx, &image, graphics::DrawParam::default().dest(area.point()).scale(scale))
+        }
+        None => Ok(()),
+    }
+}
+
+impl fmt::Display for Board {
+    /// Write the board with its coordinates (`X` for black, `O` for white)
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let (width, height) = self.size();
+        write!(f, "   ")?;
+        for x in 0..width {
+            write!(f, " {}", (b'a' + x as u8) as char)?;
+        }
+        for y in 0..height {
+            write!(f, "\n{:>3}", y + 1)?;
+            for x in 0..width {
+                write!(f, " {}", piece_char(self.get(GridPosition::new(x, y))))?;
+            }
+        }
+        Ok(())
+    }
 }
 
 impl Default for Board {
     fn default() -> Self {
-        let mut board = Board::new();
-        board.init();
-        board
+        Board::with_size(DEFAULT_GRID_SIZE)
     }
 }
 
@@ -501,13 +840,67 @@ mod tests_piece {
 #[allow(unreachable_code)]
 #[cfg(test)]
 mod tests_board {
-    use crate::{Board, GridPosition, Piece};
+    use crate::{Board, GridPosition, OthelloError, Piece};
     use grid::grid;
 
     const B: Piece = Piece::BLACK;
     const W: Piece = Piece::WHITE;
     const E: Piece = Piece::EMPTY;
 
+    #[test]
+    fn with_size() {
+        let board = Board::with_size((6, 6));
+        let expected_grid = grid![[E,E,E,E,E,E]
+                                          [E,E,E,E,E,E]
+                                          [E,E,W,B,E,E]
+                                          [E,E,B,W,E,E]
+                                          [E,E,E,E,E,E]
+                                          [E,E,E,E,E,E]];
+        assert_eq!(board, Board::set_board(expected_grid));
+        assert_eq!(board.size(), (6, 6));
+        assert_eq!(Board::default().size(), (8, 8));
+    }
+
+    #[test]
+    fn is_valid_size() {
+        assert!(Board::is_valid_size((4, 4)));
+        assert!(Board::is_valid_size((10, 6)));
+        assert!( ! Board::is_valid_size((2, 2)));
+        assert!( ! Board::is_valid_size((7, 8)));
+        assert!( ! Board::is_valid_size((8, -8)));
+        assert!(Board::is_valid_size((26, 4)));
+        assert!( ! Board::is_valid_size((28, 4)));
+        assert!( ! Board::is_valid_size((8, 28)));
+    }
+
+    #[test]
+    #[should_panic]
+    fn new_odd_size() {
+        Board::new((5, 5));
+    }
+
+    #[test]
+    fn fmt() {
+        let expected = "    a b c d\n  1 - - - -\n  2 - O X -\n  3 - X O -\n  4 - - - -";
+        assert_eq!(format!("{}", Board::with_size((4, 4))), expected);
+    }
+
+    #[test]
+    fn star_points() {
+        let points = Board::default().star_points();
+        assert_eq!(points, vec![
+            GridPosition::new(2, 2), GridPosition::new(6, 2), GridPosition::new(2, 6), GridPosition::new(6, 6),
+        ]);
+        assert_eq!(Board::with_size((10, 6)).star_points()[3], GridPosition::new(8, 4));
+        assert!(Board::with_size((4, 4)).star_points().is_empty());
+    }
+
+    #[test]
+    fn cell_size() {
+        let board = Board::with_size((10, 4));
+        assert_eq!(board.cell_size((800.0, 800.0)), (80.0, 200.0));
+    }
+
     #[test]
     fn get() {
         let grid = grid![[E,W]
@@ -590,14 +983,14 @@ mod tests_board {
     #[ignore]
     fn is_finish() {
         todo!();
-        assert!(true);
+        assert_eq!(true, true);
     }
 
     #[test]
     #[ignore]
     fn can_play() {
         todo!();
-        assert!(true);
+        assert_eq!(true, true);
     }
 
     #[test]
@@ -653,5 +1046,54 @@ mod tests_board {
         let board = Board::set_board(grid);
         assert_eq!(board.score(B), 11);
         assert_eq!(board.score(W), 10);
+
+        // More than 255 discs on the largest board
+        let board = Board::from_position_string(&format!("{} X", "X".repeat(26 * 26)), None).unwrap().0;
+        assert_eq!(board.score(B), 676);
+        assert_eq!(GridPosition::new(25, 0).to_notation(), "z1");
+    }
+
+    #[test]
+    fn play() {
+        let mut board = Board::default();
+        let outside = GridPosition::new(8, 0);
+        assert_eq!(board.try_get(outside), Err(OthelloError::OutOfBounds(outside)));
+        assert!(!board.is_valid_move(outside, B));
+        assert_eq!(board.play(outside, B), Err(OthelloError::OutOfBounds(outside)));
+        assert_eq!(board.play(GridPosition::new(3, 3), B), Err(OthelloError::Occupied(GridPosition::new(3, 3))));
+        assert_eq!(board.play(GridPosition::new(0, 0), B),
+                   Err(OthelloError::IllegalMove { position: GridPosition::new(0, 0), piece: B }));
+        assert_eq!(board.play(GridPosition::new(5, 4), E), Err(OthelloError::EmptyPiece));
+        assert_eq!(board, Board::default());
+
+        let flips = board.play(GridPosition::new(5, 4), B).unwrap();
+        assert_eq!(flips.positions, vec![GridPosition::new(4, 4)]);
+        assert_eq!(board.score(B), 4);
+    }
+
+    #[test]
+    fn update_flips() {
+        let grid = grid![[E,W,B,E]
+                                    [W,W,E,E]
+                                    [W,E,W,E]
+                                    [B,E,E,B]];
+        let mut board = Board::set_board(grid.clone());
+        let flips = board.update(GridPosition::new(0, 0), B);
+        assert_eq!(flips.len(), 5);
+        assert_eq!(flips.count(GridPosition::new(1, 0)), 1);
+        assert_eq!(flips.count(GridPosition::new(0, 1)), 2);
+        assert_eq!(flips.count(GridPosition::new(1, 1)), 2);
+        assert_eq!(flips.count(GridPosition::new(-1, 0)), 0);
+        assert!(flips.contains(GridPosition::new(2, 2)));
+        assert!(!flips.contains(GridPosition::new(2, 0)));
+        assert_eq!(flips.mask((4, 4)), vec![
+            false, true, false, false,
+            true, true, false, false,
+            true, false, true, false,
+            false, false, false, false,
+        ]);
+
+        board.undo(GridPosition::new(0, 0), &flips);
+        assert_eq!(board, Board::set_board(grid));
     }
 }
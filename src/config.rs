@@ -1,22 +1,737 @@
 //! Config file that defines every constant
+//! and the runtime configuration loaded from a TOML file
 //!
 //! | Component | axes   |
 //! |-----------|--------|
 //! | foo.0     | x-axes |
 //! | foo.1     | y-axes |
+//!
+//! Example of configuration file (every field is optional):
+//!
+//! ```toml
+//! [black]
+//! name = "Albert Einstein"
+//! type = "human"
+//!
+//! [white]
+//! name = "Marie Curie"
+//! type = "computer"
+//! level = 3
+//! # time = 0.5               # thinking time for each move (in seconds) instead of the level
+//!
+//! [theme]
+//! black = "#000000"
+//! white = "#FFFFFF"
+//! valid_moves = "#FF0000"    # "none" to hide the valid moves
+//! last_move = "#0000FF"      # "none" to not mark the last move
+//! background = "#00FF00"
+//! grid = "#FFFFFF"
+//! coordinates = "#FFFFFF"    # "none" to hide the labels around the board
+//! star_points = "#FFFFFF"    # "none" to hide the four points of the grid
+//! cursor = "#FFFF00"
+//! large_markers = false      # bigger marks of the valid moves, of the last move and of the cursor
+//! font = "/fonts/LiberationMono-Regular.ttf"
+//! font_scale = 15.0
+//! animation = 0.3            # in seconds, 0 to not animate the moves (at most 10)
+//! piece_style = "flat"       # "flat", "outline" or "shaded"
+//! texture = "none"           # an image under the grid (from resources/), like "/images/wood.png"
+//!
+//! [window]
+//! width = 1050
+//! height = 800
+//!
+//! [board]
+//! width = 8
+//! height = 8
+//!
+//! [sound]
+//! volume = 0.8               # from 0 to 1
+//! mute = false
+//! ```
+
+use std::borrow::Cow;
+use std::error::Error;
+use std::fmt;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+use directories::ProjectDirs;
+use serde::{Deserialize, Deserializer, Serialize};
+
+use crate::*;
 
-use crate::{Piece, Player};
+/// Here we define the default size of the screen (in pixel) for displaying the game
+///
+/// The board is on the left and the side panel on the right.
+/// The window can be resized, the board and the texts are scaled from this size.
+pub const SCREEN_SIZE: (u32, u32) = (1050, 800);
 
-/// Here we define the size of the screen (in pixel) for displaying the game
-pub const SCREEN_SIZE: (u32, u32) = (800, 800);
+/// The smallest size of the window (in pixel)
+pub const MIN_SCREEN_SIZE: (u32, u32) = (420, 320);
 
-/// Here we define the size of the grid in term of how many cells we will have
-pub const GRID_SIZE: (i16, i16) = (8, 8);
+/// Width of the side panel (in pixel) on a screen of the default size
+pub const PANEL_WIDTH: f32 = 250.0;
 
-/// Here we calculate the size of a cell (in pixel) in the grid
-pub const GRID_CELL_SIZE: (i16, i16) = (SCREEN_SIZE.0 as i16 / GRID_SIZE.0,
-                                        SCREEN_SIZE.1 as i16 / GRID_SIZE.1);
+/// Here we define the default size of the grid in term of how many cells we will have
+///
+/// Any even size can be used at runtime with [`Board::with_size`](crate::Board::with_size)
+pub const DEFAULT_GRID_SIZE: (i16, i16) = (8, 8);
+
+/// Largest width or height of a board, the columns are named from `a` to `z`
+pub const MAX_GRID_SIZE: i16 = 26;
 
 /// Define the two player who will played
-pub const PLAYER_ONE: Player = Player { name: "Albert Einstein", piece: Piece::BLACK};
-pub const PLAYER_TWO: Player = Player { name: "Marie Curie", piece: Piece::WHITE};
+pub const PLAYER_ONE: Player = Player {
+    name: Cow::Borrowed("Albert Einstein"),
+    piece: Piece::BLACK,
+    kind: PlayerKind::Human,
+};
+pub const PLAYER_TWO: Player = Player {
+    name: Cow::Borrowed("Marie Curie"),
+    piece: Piece::WHITE,
+    kind: PlayerKind::Human,
+};
+
+/// Volume of the sound effects when it is not specified (from 0 to 1)
+pub const DEFAULT_VOLUME: f32 = 0.8;
+
+/// Level of a computer player when it is not specified
+pub const DEFAULT_AI_LEVEL: u8 = 3;
+
+/// Longest animation of a move (in seconds)
+pub const MAX_ANIMATION: f32 = 10.0;
+
+/// Name of the configuration file in the configuration directory
+pub const CONFIG_FILE_NAME: &str = "config.toml";
+
+/// Configuration of the game, loaded from a TOML file
+///
+/// Missing values fall back to the constants of this module.
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct Config {
+    #[serde(deserialize_with = "black_player")]
+    pub black: PlayerConfig,
+    #[serde(deserialize_with = "white_player")]
+    pub white: PlayerConfig,
+    pub theme: ThemeConfig,
+    pub window: WindowConfig,
+    pub board: BoardConfig,
+    pub sound: SoundConfig,
+}
+
+/// Configuration of a player
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct PlayerConfig {
+    pub name: String,
+    #[serde(rename = "type")]
+    pub kind: PlayerType,
+    /// Level of the computer (1 to [`MAX_AI_LEVEL`]), unused for a human
+    pub level: u8,
+    /// Thinking time of the computer for each move (in seconds), the level is unused when it is set
+    pub time: Option<f64>,
+}
+
+/// Who play for a player in the configuration file
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum PlayerType {
+    Human,
+    Computer,
+}
+
+/// Configuration of the theme, colors are written as `#RRGGBB` or `#RRGGBBAA`
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct ThemeConfig {
+    pub black: String,
+    pub white: String,
+    /// Color of the valid moves, `"none"` to not draw them
+    pub valid_moves: String,
+    /// Color of the mark of the last move, `"none"` to not draw it
+    pub last_move: String,
+    pub background: String,
+    pub grid: String,
+    /// Color of the labels around the board, `"none"` to not draw them
+    pub coordinates: String,
+    /// Color of the star points of the grid, `"none"` to not draw them
+    pub star_points: String,
+    /// Color of the cell chosen with the keyboard
+    pub cursor: String,
+    /// Draw bigger marks of the valid moves, of the last move and of the cursor
+    pub large_markers: bool,
+    /// Font of texts (from resources/), must start with "/"
+    pub font: String,
+    pub font_scale: f32,
+    /// Duration of the animation of a move (in seconds), 0 to not animate the moves
+    pub animation: f32,
+    pub piece_style: PieceStyle,
+    /// Image drawn under the grid (from resources/), `"none"` to not draw it
+    pub texture: String,
+}
+
+/// Configuration of the window (in pixel)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct WindowConfig {
+    pub width: u32,
+    pub height: u32,
+}
+
+/// Configuration of the board (in cells)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct BoardConfig {
+    pub width: i16,
+    pub height: i16,
+}
+
+/// Configuration of the sound effects
+#[derive(Debug, Clone, Copy, PartialEq, Deserialize, Serialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct SoundConfig {
+    /// Volume of the sound effects, from 0 to 1
+    pub volume: f32,
+    pub mute: bool,
+}
+
+/// Error raised while loading a configuration file
+#[derive(Debug)]
+pub enum ConfigError {
+    /// The file cannot be read
+    Io(PathBuf, io::Error),
+    /// The file is not a valid TOML configuration
+    Parse(toml::de::Error),
+    /// The file is not a valid JSON theme
+    ParseJson(serde_json::Error),
+    /// The file is well formed but some values are not valid
+    Invalid(Vec<String>),
+}
+
+impl Config {
+    /// Return the path of the configuration file in the standard configuration directory
+    ///
+    /// e.g. `~/.config/rust-othello/config.toml` on Linux
+    pub fn default_path() -> Option<PathBuf> {
+        ProjectDirs::from("", "", env!("CARGO_PKG_NAME"))
+            .map(|dirs| dirs.config_dir().join(CONFIG_FILE_NAME))
+    }
+
+    /// Load the configuration
+    ///
+    /// - from `path` if given (the file must exist)
+    /// - else from [`Config::default_path`] if the file exist
+    /// - else return the default configuration
+    pub fn load(path: Option<&Path>) -> Result<Config, ConfigError> {
+        let path = match path {
+            Some(path) => path.to_path_buf(),
+            None => match Config::default_path() {
+                Some(path) if path.exists() => path,
+                _ => return Ok(Config::default()),
+            },
+        };
+        let content = fs::read_to_string(&path).map_err(|err| ConfigError::Io(path, err))?;
+        Config::parse(&content)
+    }
+
+    /// Parse and validate a configuration written in TOML
+    pub fn parse(content: &str) -> Result<Config, ConfigError> {
+        let config: Config = toml::from_str(content).map_err(ConfigError::Parse)?;
+        config.validate()?;
+        Ok(config)
+    }
+
+    /// Verify every value of the configuration, all the errors are returned together
+    pub fn validate(&self) -> Result<(), ConfigError> {
+        let mut errors = Vec::new();
+        for (color, player) in [("black", &self.black), ("white", &self.white)] {
+            if player.name.trim().is_empty() {
+                errors.push(format!("{}.name must not be empty", color));
+            }
+            if player.kind == PlayerType::Computer && !(1..=MAX_AI_LEVEL).contains(&player.level) {
+                errors.push(format!("{}.level must be between 1 and {} (found {})",
+                    color, MAX_AI_LEVEL, player.level));
+            }
+            if player.time.is_some_and(|time| !time.is_finite() || time <= 0.0) {
+                errors.push(format!("{}.time must be a positive number of seconds (found {})",
+                    color, player.time.unwrap_or_default()));
+            }
+        }
+        errors.extend(self.theme.errors());
+        if self.window.width == 0 || self.window.height == 0 {
+            errors.push(format!("window size must not be null (found {}x{})",
+                self.window.width, self.window.height));
+        }
+        if !Board::is_valid_size(self.board_size()) {
+            errors.push(format!("board size must be even, at least 4x4 and at most 26x26 (found {}x{})",
+                self.board.width, self.board.height));
+        }
+        if !(0.0..=1.0).contains(&self.sound.volume) {
+            errors.push(format!("sound.volume must be between 0 and 1 (found {})", self.sound.volume));
+        }
+
+        if errors.is_empty() {
+            Ok(())
+        } else {
+            Err(ConfigError::Invalid(errors))
+        }
+    }
+
+    /// Create the players described by the configuration
+    ///
+    /// Return (player_black, player_white)
+    pub fn players(&self) -> (Player, Player) {
+        (self.black.player(Piece::BLACK), self.white.player(Piece::WHITE))
+    }
+
+    /// Create the theme described by the configuration
+    pub fn theme(&self) -> Theme {
+        self.theme.theme()
+    }
+
+    /// Return the size of the board (columns, rows)
+    pub fn board_size(&self) -> (i16, i16) {
+        (self.board.width, self.board.height)
+    }
+
+    /// Return the size of the window (in pixel)
+    pub fn window_size(&self) -> (f32, f32) {
+        (self.window.width as f32, self.window.height as f32)
+    }
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Config {
+            black: PlayerConfig::from(&PLAYER_ONE),
+            white: PlayerConfig::from(&PLAYER_TWO),
+            theme: ThemeConfig::default(),
+            window: WindowConfig::default(),
+            board: BoardConfig::default(),
+            sound: SoundConfig::default(),
+        }
+    }
+}
+
+impl PlayerConfig {
+    /// Create the player described by this configuration
+    pub fn player(&self, piece: Piece) -> Player {
+        match self.kind {
+            PlayerType::Human => Player::new(self.name.clone(), piece),
+            PlayerType::Computer => match self.time.and_then(|time| Duration::try_from_secs_f64(time).ok()) {
+                Some(time) if !time.is_zero() => Player {
+                    name: Cow::Owned(self.name.clone()),
+                    piece,
+                    kind: PlayerKind::Computer(Engine::with_time_limit(time)),
+                },
+                _ => Player::computer(self.name.clone(), piece, self.level),
+            },
+        }
+    }
+}
+
+impl From<&Player> for PlayerConfig {
+    fn from(player: &Player) -> Self {
+        let (kind, level, time) = match player.kind {
+            PlayerKind::Human => (PlayerType::Human, DEFAULT_AI_LEVEL, None),
+            PlayerKind::Computer(engine) => match engine.time_limit() {
+                // The depth of an engine limited by time is not a level
+                Some(time) => (PlayerType::Computer, DEFAULT_AI_LEVEL, Some(time.as_secs_f64())),
+                None => (PlayerType::Computer, engine.depth(), None),
+            },
+        };
+        PlayerConfig { name: player.name.to_string(), kind, level, time }
+    }
+}
+
+/// A player of the configuration file where every field is optional
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct PartialPlayerConfig {
+    name: Option<String>,
+    #[serde(rename = "type")]
+    kind: Option<PlayerType>,
+    level: Option<u8>,
+    time: Option<f64>,
+}
+
+/// Read a player of the configuration, the missing fields are the ones of `default`
+fn player_or<'de, D: Deserializer<'de>>(deserializer: D, default: &Player) -> Result<PlayerConfig, D::Error> {
+    let player = PartialPlayerConfig::deserialize(deserializer)?;
+    let default = PlayerConfig::from(default);
+    Ok(PlayerConfig {
+        name: player.name.unwrap_or(default.name),
+        kind: player.kind.unwrap_or(default.kind),
+        level: player.level.unwrap_or(default.level),
+        time: player.time.or(default.time),
+    })
+}
+
+/// Read the black player, by default [`PLAYER_ONE`]
+fn black_player<'de, D: Deserializer<'de>>(deserializer: D) -> Result<PlayerConfig, D::Error> {
+    player_or(deserializer, &PLAYER_ONE)
+}
+
+/// Read the white player, by default [`PLAYER_TWO`]
+fn white_player<'de, D: Deserializer<'de>>(deserializer: D) -> Result<PlayerConfig, D::Error> {
+    player_or(deserializer, &PLAYER_TWO)
+}
+
+impl Default for PlayerConfig {
+    fn default() -> Self {
+        PlayerConfig {
+            name: String::from("Player"),
+            kind: PlayerType::Human,
+            level: DEFAULT_AI_LEVEL,
+            time: None,
+        }
+    }
+}
+
+impl ThemeConfig {
+    /// Load a theme file, written in JSON if its extension is `.json` else in TOML
+    ///
+    /// A theme file have the same fields as the `[theme]` table of the configuration file.
+    pub fn load(path: &Path) -> Result<ThemeConfig, ConfigError> {
+        let content = fs::read_to_string(path).map_err(|err| ConfigError::Io(path.to_path_buf(), err))?;
+        if path.extension().is_some_and(|extension| extension == "json") {
+            ThemeConfig::parse_json(&content)
+        } else {
+            ThemeConfig::parse(&content)
+        }
+    }
+
+    /// Parse and validate a theme written in TOML
+    pub fn parse(content: &str) -> Result<ThemeConfig, ConfigError> {
+        let theme: ThemeConfig = toml::from_str(content).map_err(ConfigError::Parse)?;
+        theme.validate()?;
+        Ok(theme)
+    }
+
+    /// Parse and validate a theme written in JSON
+    pub fn parse_json(content: &str) -> Result<ThemeConfig, ConfigError> {
+        let theme: ThemeConfig = serde_json::from_str(content).map_err(ConfigError::ParseJson)?;
+        theme.validate()?;
+        Ok(theme)
+    }
+
+    /// Verify every value of the theme, all the errors are returned together
+    pub fn validate(&self) -> Result<(), ConfigError> {
+        let errors = self.errors();
+        if errors.is_empty() {
+            Ok(())
+        } else {
+            Err(ConfigError::Invalid(errors))
+        }
+    }
+
+    /// List the invalid values of the theme
+    fn errors(&self) -> Vec<String> {
+        let mut errors = Vec::new();
+        for (field, value) in [
+            ("black", &self.black),
+            ("white", &self.white),
+            ("background", &self.background),
+            ("grid", &self.grid),
+            ("cursor", &self.cursor),
+        ] {
+            if color_from_hex(value).is_none() {
+                errors.push(format!("theme.{} is not a color like \"#RRGGBB\" (found \"{}\")", field, value));
+            }
+        }
+        for (field, value) in [
+            ("valid_moves", &self.valid_moves),
+            ("last_move", &self.last_move),
+            ("coordinates", &self.coordinates),
+            ("star_points", &self.star_points),
+        ] {
+            if value != "none" && color_from_hex(value).is_none() {
+                errors.push(format!("theme.{} is not a color like \"#RRGGBB\" or \"none\" (found \"{}\")",
+                    field, value));
+            }
+        }
+        if !self.font.starts_with('/') {
+            errors.push(format!("theme.font must start with \"/\" (found \"{}\")", self.font));
+        }
+        if self.texture != "none" && !self.texture.starts_with('/') {
+            errors.push(format!("theme.texture must start with \"/\" or be \"none\" (found \"{}\")", self.texture));
+        }
+        if self.font_scale.is_nan() || self.font_scale <= 0.0 {
+            errors.push(format!("theme.font_scale must be positive (found {})", self.font_scale));
+        }
+        if !(0.0..=MAX_ANIMATION).contains(&self.animation) {
+            errors.push(format!("theme.animation must be between 0 and {} seconds (found {})",
+                MAX_ANIMATION, self.animation));
+        }
+        errors
+    }
+
+    /// Create the theme described by this configuration
+    ///
+    /// Invalid colors are replaced by the ones of [`DEFAULT_THEME`]
+    pub fn theme(&self) -> Theme {
+        Theme {
+            piece_colors: (
+                color_from_hex(&self.black).unwrap_or(DEFAULT_THEME.piece_colors.0),
+                color_from_hex(&self.white).unwrap_or(DEFAULT_THEME.piece_colors.1),
+            ),
+            valid_moves_color: match self.valid_moves.as_str() {
+                "none" => None,
+                color => color_from_hex(color).or(DEFAULT_THEME.valid_moves_color),
+            },
+            last_move_color: match self.last_move.as_str() {
+                "none" => None,
+                color => color_from_hex(color).or(DEFAULT_THEME.last_move_color),
+            },
+            background_color: color_from_hex(&self.background).unwrap_or(DEFAULT_THEME.background_color),
+            grid_color: color_from_hex(&self.grid).unwrap_or(DEFAULT_THEME.grid_color),
+            coordinates_color: match self.coordinates.as_str() {
+                "none" => None,
+                color => color_from_hex(color).or(DEFAULT_THEME.coordinates_color),
+            },
+            star_points_color: match self.star_points.as_str() {
+                "none" => None,
+                color => color_from_hex(color).or(DEFAULT_THEME.star_points_color),
+            },
+            cursor_color: color_from_hex(&self.cursor).unwrap_or(DEFAULT_THEME.cursor_color),
+            large_markers: self.large_markers,
+            font_path: Cow::Owned(self.font.clone()),
+            font_scale: self.font_scale,
+            animation_duration: (self.animation > 0.0)
+                .then(|| Duration::from_secs_f32(self.animation.min(MAX_ANIMATION))),
+            piece_style: self.piece_style,
+            board_texture: (self.texture != "none").then(|| Cow::Owned(self.texture.clone())),
+        }
+    }
+}
+
+impl From<&Theme> for ThemeConfig {
+    fn from(theme: &Theme) -> Self {
+        ThemeConfig {
+            black: color_to_hex(theme.piece_colors.0),
+            white: color_to_hex(theme.piece_colors.1),
+            valid_moves: theme.valid_moves_color
+                .map(color_to_hex)
+                .unwrap_or_else(|| String::from("none")),
+            last_move: theme.last_move_color
+                .map(color_to_hex)
+                .unwrap_or_else(|| String::from("none")),
+            background: color_to_hex(theme.background_color),
+            grid: color_to_hex(theme.grid_color),
+            coordinates: theme.coordinates_color
+                .map(color_to_hex)
+                .unwrap_or_else(|| String::from("none")),
+            star_points: theme.star_points_color
+                .map(color_to_hex)
+                .unwrap_or_else(|| String::from("none")),
+            cursor: color_to_hex(theme.cursor_color),
+            large_markers: theme.large_markers,
+            font: theme.font_path.to_string(),
+            font_scale: theme.font_scale,
+            animation: theme.animation_duration.map_or(0.0, |duration| duration.as_secs_f32()),
+            piece_style: theme.piece_style,
+            texture: theme.board_texture.as_deref().unwrap_or("none").to_string(),
+        }
+    }
+}
+
+impl Default for ThemeConfig {
+    fn default() -> Self {
+        ThemeConfig::from(&DEFAULT_THEME)
+    }
+}
+
+impl Default for WindowConfig {
+    fn default() -> Self {
+        WindowConfig { width: SCREEN_SIZE.0, height: SCREEN_SIZE.1 }
+    }
+}
+
+impl Default for SoundConfig {
+    fn default() -> Self {
+        SoundConfig { volume: DEFAULT_VOLUME, mute: false }
+    }
+}
+
+impl Default for BoardConfig {
+    fn default() -> Self {
+        BoardConfig { width: DEFAULT_GRID_SIZE.0, height: DEFAULT_GRID_SIZE.1 }
+    }
+}
+
+impl fmt::Display for ConfigError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ConfigError::Io(path, err) => write!(f, "cannot read {}: {}", path.display(), err),
+            ConfigError::Parse(err) => write!(f, "invalid configuration file: {}", err),
+            ConfigError::ParseJson(err) => write!(f, "invalid theme file: {}", err),
+            ConfigError::Invalid(errors) => {
+                write!(f, "invalid configuration:")?;
+                for error in errors {
+                    write!(f, "\n  - {}", error)?;
+                }
+                Ok(())
+            }
+        }
+    }
+}
+
+impl Error for ConfigError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            ConfigError::Io(_, err) => Some(err),
+            ConfigError::Parse(err) => Some(err),
+            ConfigError::ParseJson(err) => Some(err),
+            ConfigError::Invalid(_) => None,
+        }
+    }
+}
+
+#[allow(unreachable_code)]
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use ggez::graphics::Color;
+
+    use crate::*;
+
+    #[test]
+    fn default_config() {
+        let config = Config::default();
+        assert!(config.validate().is_ok());
+        assert_eq!(config.players(), (PLAYER_ONE, PLAYER_TWO));
+        assert_eq!(config.board_size(), DEFAULT_GRID_SIZE);
+        assert_eq!(config.window_size(), (SCREEN_SIZE.0 as f32, SCREEN_SIZE.1 as f32));
+    }
+
+    #[test]
+    fn parse() {
+        let config = Config::parse(r##"
+            [white]
+            name = "Deep Thought"
+            type = "computer"
+            level = 2
+
+            [theme]
+            background = "#004000"
+            valid_moves = "none"
+            coordinates = "none"
+            animation = 0
+
+            [board]
+            width = 6
+            height = 6
+
+            [sound]
+            mute = true
+        "##).unwrap();
+
+        let (black, white) = config.players();
+        assert_eq!(black, PLAYER_ONE);
+        assert_eq!(white, Player::computer("Deep Thought", Piece::WHITE, 2));
+        assert_eq!(config.theme().background_color, color_from_hex("#004000").unwrap());
+        assert_eq!(config.theme().valid_moves_color, None);
+        assert_eq!(config.theme().coordinates_color, None);
+        assert_eq!(config.theme().star_points_color, DEFAULT_THEME.star_points_color);
+        assert_eq!(config.theme().animation_duration, None);
+        assert_eq!(config.theme().last_move_color, DEFAULT_THEME.last_move_color);
+        assert_eq!(config.board_size(), (6, 6));
+        assert_eq!(config.sound, SoundConfig { volume: DEFAULT_VOLUME, mute: true });
+    }
+
+    #[test]
+    fn parse_partial_player() {
+        let config = Config::parse("[black]\ntype = \"computer\"\n\n[white]\nlevel = 5").unwrap();
+        let (black, white) = config.players();
+        assert_eq!(black, Player::computer("Albert Einstein", Piece::BLACK, DEFAULT_AI_LEVEL));
+        assert_eq!(white, PLAYER_TWO);
+        assert_eq!(config.white.level, 5);
+        assert!(matches!(Config::parse("[black]\nlevels = 3"), Err(ConfigError::Parse(_))));
+    }
+
+    #[test]
+    fn timed_computer() {
+        let player = Player {
+            kind: PlayerKind::Computer(Engine::with_time_limit(Duration::from_millis(500))),
+            ..Player::computer("Deep Blue", Piece::WHITE, 1)
+        };
+        let config = PlayerConfig::from(&player);
+        assert_eq!(config.time, Some(0.5));
+        assert_eq!(config.player(Piece::WHITE), player);
+
+        let config = Config::parse("[white]\ntype = \"computer\"\ntime = 0.25").unwrap();
+        assert_eq!(config.players().1.kind, PlayerKind::Computer(Engine::with_time_limit(Duration::from_millis(250))));
+        match Config::parse("[white]\ntime = -1.0") {
+            Err(ConfigError::Invalid(errors)) => assert_eq!(errors.len(), 1),
+            _ => panic!("the configuration must be invalid"),
+        }
+    }
+
+    #[test]
+    fn parse_unknown_field() {
+        assert!(matches!(Config::parse("[window]\ndepth = 3"), Err(ConfigError::Parse(_))));
+    }
+
+    #[test]
+    fn validate_list_every_error() {
+        let result = Config::parse(r##"
+            [black]
+            name = ""
+            type = "computer"
+            level = 0
+
+            [theme]
+            grid = "white"
+            animation = -1.0
+
+            [board]
+            width = 7
+        "##);
+        match result {
+            Err(ConfigError::Invalid(errors)) => assert_eq!(errors.len(), 5),
+            _ => panic!("the configuration must be invalid"),
+        }
+    }
+
+    #[test]
+    fn theme_file() {
+        let theme = ThemeConfig::parse(r##"
+            background = "#202020"
+            piece_style = "outline"
+            texture = "/images/wood.png"
+        "##).unwrap().theme();
+        assert_eq!(theme.background_color, color_from_hex("#202020").unwrap());
+        assert_eq!(theme.piece_style, PieceStyle::Outline);
+        assert_eq!(theme.board_texture.as_deref(), Some("/images/wood.png"));
+
+        let theme = ThemeConfig::parse_json(r##"{"grid": "#000000", "piece_style": "shaded"}"##).unwrap().theme();
+        assert_eq!(theme.grid_color, Color::BLACK);
+        assert_eq!(theme.piece_style, PieceStyle::Shaded);
+        assert_eq!(theme.board_texture, None);
+
+        assert!(matches!(ThemeConfig::parse_json("{\"grid\": 3}"), Err(ConfigError::ParseJson(_))));
+        match ThemeConfig::parse_json(r##"{"texture": "wood.png", "last_move": "blue"}"##) {
+            Err(ConfigError::Invalid(errors)) => assert_eq!(errors.len(), 2),
+            _ => panic!("the theme must be invalid"),
+        }
+
+        // A too long animation is refused, and shortened if the theme is not validated
+        let config = ThemeConfig::parse_json(r##"{"animation": 1e30}"##);
+        assert!(matches!(config, Err(ConfigError::Invalid(_))));
+        let config = ThemeConfig { animation: 1e30, ..ThemeConfig::default() };
+        assert_eq!(config.theme().animation_duration, Some(Duration::from_secs_f32(MAX_ANIMATION)));
+    }
+
+    #[test]
+    fn gallery_round_trip() {
+        let config = ThemeConfig::from(&DARK_THEME);
+        assert!(config.validate().is_ok());
+        let theme = config.theme();
+        assert_eq!(theme.piece_style, DARK_THEME.piece_style);
+        assert_eq!(theme.background_color.to_rgba(), DARK_THEME.background_color.to_rgba());
+    }
+}
@@ -0,0 +1,427 @@
+//! Diagrams of a board for articles: a PNG drawn with ggez, a SVG or a [`Raster`] drawn without window
+//!
+//! ```
+//! use rust_othello::*;
+//!
+//! let mut board = Board::default();
+//! let moves = parse_transcript("f5d6").unwrap();
+//! play_transcript(&mut board, Piece::BLACK, &moves).unwrap();
+//! let svg = Diagram::new(&board)
+//!     .set_moves(&moves)
+//!     .set_highlights(&[GridPosition::new(2, 2)])
+//!     .to_svg();
+//! assert!(svg.starts_with("<svg"));
+//! ```
+
+use std::fmt::Write;
+use std::path::Path;
+
+use ab_glyph::FontVec;
+use ggez::{conf, Context, GameResult};
+use ggez::graphics::{self, Color, Rect};
+use glam::Vec2;
+
+use crate::*;
+
+/// Size of a cell of a diagram when it is not specified (in pixel)
+pub const DEFAULT_DIAGRAM_CELL_SIZE: f32 = 50.0;
+
+/// A diagram of a board with the numbers of the moves on the discs and highlighted cases
+///
+/// The coordinates are drawn if the theme draw them.
+#[derive(Debug, Clone)]
+pub struct Diagram {
+    board: Board,
+    theme: Theme,
+    /// Number written on each case (the number of the move that placed the disc)
+    numbers: Vec<(GridPosition, usize)>,
+    highlights: Vec<GridPosition>,
+    /// Case marked with the color of the last move of the theme
+    last_move: Option<GridPosition>,
+    cell_size: f32,
+}
+
+impl Diagram {
+    /// Create the diagram of a board with the default theme
+    pub fn new(board: &Board) -> Self {
+        Diagram {
+            board: board.clone(),
+            theme: Theme::default(),
+            numbers: Vec::new(),
+            highlights: Vec::new(),
+            last_move: None,
+            cell_size: DEFAULT_DIAGRAM_CELL_SIZE,
+        }
+    }
+
+    /// Set the theme of the diagram (colors, coordinates and piece style)
+    pub fn set_theme(mut self, theme: Theme) -> Self {
+        self.theme = theme;
+        self
+    }
+
+    /// Write the number of each move on its disc, the first move is 1
+    ///
+    /// If a case is played twice (e.g. after an edition) the last number is kept.
+    pub fn set_moves(mut self, moves: &[GridPosition]) -> Self {
+        self.numbers = moves.iter().enumerate().map(|(index, position)| (*position, index + 1)).collect();
+        self
+    }
+
+    /// Highlight some cases with the color of the cursor of the theme
+    pub fn set_highlights(mut self, highlights: &[GridPosition]) -> Self {
+        self.highlights = highlights.to_vec();
+        self
+    }
+
+    /// Mark the last played move like in a game (if the theme mark it)
+    pub fn set_last_move(mut self, last_move: Option<GridPosition>) -> Self {
+        self.last_move = last_move;
+        self
+    }
+
+    /// Set the size of a cell (in pixel)
+    pub fn set_cell_size(mut self, cell_size: f32) -> Self {
+        self.cell_size = cell_size;
+        self
+    }
+
+    /// Return the size of the image (in pixel)
+    pub fn size(&self) -> (f32, f32) {
+        let (width, height) = self.board.size();
+        let grid = (width as f32 * self.cell_size, height as f32 * self.cell_size);
+        // The margin of the coordinates is 1/20 of the image, see `Theme::grid_area`
+        let margin = match self.theme.coordinates_color {
+            Some(_) => grid.0.min(grid.1) / 18.0,
+            None => 0.0,
+        };
+        (grid.0 + 2.0 * margin, grid.1 + 2.0 * margin)
+    }
+
+    /// Return the area of the grid in the image
+    fn grid(&self) -> Rect {
+        let (width, height) = self.size();
+        self.theme.grid_area(Rect::new(0.0, 0.0, width, height))
+    }
+
+    /// Return the center of a case in the image
+    fn center(&self, position: GridPosition) -> Vec2 {
+        let grid = self.grid();
+        Vec2::new(
+            grid.x + (position.x as f32 + 0.5) * self.cell_size,
+            grid.y + (position.y as f32 + 0.5) * self.cell_size,
+        )
+    }
+
+    /// Return the numbers to write: only the ones on a disc, the last number of a case
+    fn numbers(&self) -> Vec<(GridPosition, usize)> {
+        let mut numbers: Vec<(GridPosition, usize)> = Vec::new();
+        for (position, number) in &self.numbers {
+            numbers.retain(|(other, _)| other != position);
+            if self.board.try_get(*position).is_ok_and(|piece| piece != Piece::EMPTY) {
+                numbers.push((*position, *number));
+            }
+        }
+        numbers
+    }
+
+    /// Return the radius of the mark of the last move, see [`Board::draw_animated`]
+    fn last_move_radius(&self) -> f32 {
+        self.cell_size / if self.theme.large_markers { 4.0 } else { 10.0 }
+    }
+
+    /// Return the color of a text written on a disc
+    fn number_color(&self, piece: Piece) -> Color {
+        match piece {
+            Piece::BLACK => self.theme.piece_colors.1,
+            _ => self.theme.piece_colors.0,
+        }
+    }
+
+    /// Draw the diagram with ggez in the area (0, 0, width, height) of [`Diagram::size`]
+    pub fn draw(&self, ctx: &mut Context) -> GameResult {
+        let (width, height) = self.size();
+        let area = Rect::new(0.0, 0.0, width, height);
+        graphics::clear(ctx, self.theme.background_color);
+
+        for position in &self.highlights {
+            let center = self.center(*position);
+            let cell = Rect::new(center.x - self.cell_size / 2.0, center.y - self.cell_size / 2.0, self.cell_size, self.cell_size);
+            let mut color = self.theme.cursor_color;
+            color.a *= 0.6;
+            let mesh = graphics::MeshBuilder::new()
+                .rectangle(graphics::DrawMode::fill(), cell, color)?
+                .build(ctx)?;
+            graphics::draw(ctx, &mesh, graphics::DrawParam::default())?;
+        }
+
+        // The valid moves are not drawn on a diagram
+        let theme = Theme { valid_moves_color: None, ..self.theme.clone() };
+        self.board.draw_animated(ctx, area, Piece::EMPTY, &theme, None)?;
+        if let (Some(position), Some(color)) = (self.last_move, self.theme.last_move_color) {
+            let mesh = graphics::MeshBuilder::new()
+                .circle(graphics::DrawMode::fill(), self.center(position), self.last_move_radius(), 1.0, color)?
+                .build(ctx)?;
+            graphics::draw(ctx, &mesh, graphics::DrawParam::default())?;
+        }
+
+        let font = graphics::Font::new(ctx, self.theme.font_path.as_ref())?;
+        for (position, number) in self.numbers() {
+            let text = graphics::Text::new((number.to_string(), font, 0.4 * self.cell_size));
+            let dimensions = text.dimensions(ctx);
+            let point = self.center(position) - Vec2::new(dimensions.w / 2.0, dimensions.h / 2.0);
+            graphics::draw(ctx, &text, (point, self.number_color(self.board.get(position))))?;
+        }
+        Ok(())
+    }
+
+    /// Draw the diagram on a canvas and save it as a PNG in the user data directory of ggez
+    ///
+    /// `path` must start with "/", e.g. "/diagram.png".
+    pub fn save_png<P: AsRef<Path>>(&self, ctx: &mut Context, path: P) -> GameResult {
+        let (width, height) = self.size();
+        let canvas = graphics::Canvas::new(
+            ctx,
+            width.ceil() as u16,
+            height.ceil() as u16,
+            conf::NumSamples::One,
+            graphics::get_window_color_format(ctx),
+        )?;
+        let screen = graphics::screen_coordinates(ctx);
+        graphics::set_canvas(ctx, Some(&canvas));
+        graphics::set_screen_coordinates(ctx, Rect::new(0.0, 0.0, width, height))?;
+        let result = self.draw(ctx);
+        graphics::set_canvas(ctx, None);
+        graphics::set_screen_coordinates(ctx, screen)?;
+        result?;
+        canvas.encode(ctx, graphics::ImageFormat::Png, path)
+    }
+
+    /// Write the diagram as a SVG image, no window is needed
+    pub fn to_svg(&self) -> String {
+        let (width, height) = self.size();
+        let grid = self.grid();
+        let (columns, rows) = self.board.size();
+        let theme = &self.theme;
+        let mut svg = String::new();
+
+        // `write!` in a `String` cannot fail
+        let _ = writeln!(svg, r#"<svg xmlns="http://www.w3.org/2000/svg" width="{w}" height="{h}" viewBox="0 0 {w} {h}">"#,
+            w = width, h = height);
+        let _ = writeln!(svg, r#"<rect width="{}" height="{}" fill="{}"/>"#, width, height, color_to_hex(theme.background_color));
+        for position in &self.highlights {
+            let center = self.center(*position);
+            let _ = writeln!(svg, r#"<rect x="{}" y="{}" width="{s}" height="{s}" fill="{}" fill-opacity="0.6"/>"#,
+                center.x - self.cell_size / 2.0, center.y - self.cell_size / 2.0, color_to_hex(theme.cursor_color), s = self.cell_size);
+        }
+
+        // The grid and the star points
+        let grid_color = color_to_hex(theme.grid_color);
+        for x in 1..columns {
+            let x = grid.x + x as f32 * self.cell_size;
+            let _ = writeln!(svg, r#"<line x1="{x}" y1="{}" x2="{x}" y2="{}" stroke="{}" stroke-width="2"/>"#,
+                grid.top(), grid.bottom(), grid_color, x = x);
+        }
+        for y in 1..rows {
+            let y = grid.y + y as f32 * self.cell_size;
+            let _ = writeln!(svg, r#"<line x1="{}" y1="{y}" x2="{}" y2="{y}" stroke="{}" stroke-width="2"/>"#,
+                grid.left(), grid.right(), grid_color, y = y);
+        }
+        if let Some(color) = theme.star_points_color {
+            for point in self.board.star_points() {
+                let _ = writeln!(svg, r#"<circle cx="{}" cy="{}" r="{}" fill="{}"/>"#,
+                    grid.x + point.x as f32 * self.cell_size, grid.y + point.y as f32 * self.cell_size,
+                    self.cell_size / 12.0, color_to_hex(color));
+            }
+        }
+
+        // The discs and their numbers
+        let radius = 2.0 * self.cell_size / 5.0;
+        for y in 0..rows {
+            for x in 0..columns {
+                let position = GridPosition::new(x, y);
+                let color = match self.board.get(position) {
+                    Piece::BLACK => theme.piece_colors.0,
+                    Piece::WHITE => theme.piece_colors.1,
+                    Piece::EMPTY => continue,
+                };
+                let center = self.center(position);
+                let border = match theme.piece_style {
+                    PieceStyle::Outline => format!(r#" stroke="{}" stroke-width="{}""#, grid_color, radius / 8.0),
+                    _ => String::new(),
+                };
+                let _ = writeln!(svg, r#"<circle cx="{}" cy="{}" r="{}" fill="{}"{}/>"#,
+                    center.x, center.y, radius, color_to_hex(color), border);
+                if theme.piece_style == PieceStyle::Shaded {
+                    let _ = writeln!(svg, r##"<circle cx="{}" cy="{}" r="{}" fill="#FFFFFF" fill-opacity="0.3"/>"##,
+                        center.x - radius / 3.0, center.y - radius / 3.0, radius / 3.0);
+                }
+            }
+        }
+        if let (Some(position), Some(color)) = (self.last_move, theme.last_move_color) {
+            let center = self.center(position);
+            let _ = writeln!(svg, r#"<circle cx="{}" cy="{}" r="{}" fill="{}"/>"#,
+                center.x, center.y, self.last_move_radius(), color_to_hex(color));
+        }
+        for (position, number) in self.numbers() {
+            let center = self.center(position);
+            let color = self.number_color(self.board.get(position));
+            let _ = writeln!(svg, r#"<text x="{}" y="{}" {} font-size="{}" fill="{}">{}</text>"#,
+                center.x, center.y, TEXT_STYLE, 0.4 * self.cell_size, color_to_hex(color), number);
+        }
+
+        // The coordinates around the grid
+        if let Some(color) = theme.coordinates_color {
+            let margin = grid.x;
+            let color = color_to_hex(color);
+            let mut label = |x: f32, y: f32, text: &str| {
+                let _ = writeln!(svg, r#"<text x="{}" y="{}" {} font-size="{}" fill="{}">{}</text>"#,
+                    x, y, TEXT_STYLE, 0.6 * margin, color, text);
+            };
+            for x in 0..columns {
+                let center = self.center(GridPosition::new(x, 0)).x;
+                let text = column_label(x).to_string();
+                label(center, margin / 2.0, &text);
+                label(center, height - margin / 2.0, &text);
+            }
+            for y in 0..rows {
+                let center = self.center(GridPosition::new(0, y)).y;
+                let text = row_label(y);
+                label(margin / 2.0, center, &text);
+                label(width - margin / 2.0, center, &text);
+            }
+        }
+        svg.push_str("</svg>\n");
+        svg
+    }
+
+    /// Draw the diagram without window in the top left corner of a [`Raster`]
+    ///
+    /// The texts are written with `font`, see [`load_font`]. The texture of the theme is not drawn.
+    pub fn draw_raster(&self, raster: &mut Raster, font: &FontVec) {
+        let (width, height) = self.size();
+        let grid = self.grid();
+        let (columns, rows) = self.board.size();
+        let theme = &self.theme;
+        raster.fill_rect(Rect::new(0.0, 0.0, width, height), theme.background_color);
+        for position in &self.highlights {
+            let center = self.center(*position);
+            let mut color = theme.cursor_color;
+            color.a *= 0.6;
+            raster.fill_rect(Rect::new(center.x - self.cell_size / 2.0, center.y - self.cell_size / 2.0, self.cell_size, self.cell_size), color);
+        }
+
+        // The grid and the star points
+        for x in 1..columns {
+            let x = grid.x + x as f32 * self.cell_size;
+            raster.fill_rect(Rect::new(x - 1.0, grid.y, 2.0, grid.h), theme.grid_color);
+        }
+        for y in 1..rows {
+            let y = grid.y + y as f32 * self.cell_size;
+            raster.fill_rect(Rect::new(grid.x, y - 1.0, grid.w, 2.0), theme.grid_color);
+        }
+        if let Some(color) = theme.star_points_color {
+            for point in self.board.star_points() {
+                let center = Vec2::new(grid.x + point.x as f32 * self.cell_size, grid.y + point.y as f32 * self.cell_size);
+                raster.fill_circle(center, self.cell_size / 12.0, color);
+            }
+        }
+
+        // The discs, the last move and the numbers
+        let radius = 2.0 * self.cell_size / 5.0;
+        for y in 0..rows {
+            for x in 0..columns {
+                let position = GridPosition::new(x, y);
+                let color = match self.board.get(position) {
+                    Piece::BLACK => theme.piece_colors.0,
+                    Piece::WHITE => theme.piece_colors.1,
+                    Piece::EMPTY => continue,
+                };
+                let center = self.center(position);
+                raster.fill_circle(center, radius, color);
+                match theme.piece_style {
+                    PieceStyle::Flat => {}
+                    PieceStyle::Outline => raster.stroke_circle(center, radius, radius / 8.0, theme.grid_color),
+                    PieceStyle::Shaded => raster.fill_circle(
+                        center - Vec2::new(radius / 3.0, radius / 3.0),
+                        radius / 3.0,
+                        Color::new(1.0, 1.0, 1.0, 0.3),
+                    ),
+                }
+            }
+        }
+        if let (Some(position), Some(color)) = (self.last_move, theme.last_move_color) {
+            raster.fill_circle(self.center(position), self.last_move_radius(), color);
+        }
+        for (position, number) in self.numbers() {
+            let color = self.number_color(self.board.get(position));
+            raster.text(font, &number.to_string(), self.center(position), 0.4 * self.cell_size, color);
+        }
+
+        // The coordinates around the grid
+        if let Some(color) = theme.coordinates_color {
+            let margin = grid.x;
+            for x in 0..columns {
+                let center = self.center(GridPosition::new(x, 0)).x;
+                let text = column_label(x).to_string();
+                raster.text(font, &text, Vec2::new(center, margin / 2.0), 0.6 * margin, color);
+                raster.text(font, &text, Vec2::new(center, height - margin / 2.0), 0.6 * margin, color);
+            }
+            for y in 0..rows {
+                let center = self.center(GridPosition::new(0, y)).y;
+                let text = row_label(y);
+                raster.text(font, &text, Vec2::new(margin / 2.0, center), 0.6 * margin, color);
+                raster.text(font, &text, Vec2::new(width - margin / 2.0, center), 0.6 * margin, color);
+            }
+        }
+    }
+}
+
+/// Attributes of the texts of a SVG diagram: centred on their position
+const TEXT_STYLE: &str = r#"font-family="monospace" text-anchor="middle" dominant-baseline="central""#;
+
+#[allow(unreachable_code)]
+#[cfg(test)]
+mod tests {
+    use crate::*;
+
+    #[test]
+    fn size() {
+        let diagram = Diagram::new(&Board::default());
+        assert_eq!(diagram.size(), (400.0 + 400.0 / 9.0, 400.0 + 400.0 / 9.0));
+        let theme = Theme { coordinates_color: None, ..Theme::default() };
+        let diagram = diagram.set_theme(theme).set_cell_size(10.0);
+        assert_eq!(diagram.size(), (80.0, 80.0));
+    }
+
+    #[test]
+    fn svg() {
+        let mut board = Board::default();
+        let moves = parse_transcript("f5d6").unwrap();
+        play_transcript(&mut board, Piece::BLACK, &moves).unwrap();
+        let theme = Theme { coordinates_color: None, star_points_color: None, ..Theme::default() };
+        let svg = Diagram::new(&board)
+            .set_theme(theme)
+            .set_moves(&moves)
+            .set_highlights(&[GridPosition::new(2, 2)])
+            .to_svg();
+
+        assert!(svg.starts_with(r#"<svg xmlns="http://www.w3.org/2000/svg" width="400" height="400""#));
+        assert!(svg.ends_with("</svg>\n"));
+        assert_eq!(svg.matches("<line").count(), 14);
+        assert_eq!(svg.matches("<circle").count(), 6);
+        assert_eq!(svg.matches("fill-opacity=\"0.6\"").count(), 1);
+        // The numbers of f5 (black disc) and d6 (white disc)
+        assert!(svg.contains(r##"<text x="275" y="225" "##));
+        assert!(svg.contains(r##"fill="#FFFFFF">1</text>"##));
+        assert!(svg.contains(r##"fill="#000000">2</text>"##));
+    }
+
+    #[test]
+    fn coordinates() {
+        let svg = Diagram::new(&Board::with_size((4, 4))).to_svg();
+        assert!(svg.contains(">a</text>"));
+        assert!(svg.contains(">4</text>"));
+        assert!(!svg.contains(">e</text>"));
+    }
+}
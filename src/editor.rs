@@ -0,0 +1,345 @@
+//! Board editor: set up any position, then play from it, analyze it or export it
+//!
+//! A click cycles a case between empty, black and white, without recompiling a `grid!`.
+
+use std::error::Error;
+use std::fmt;
+use std::fs;
+
+use ggez::{Context, GameResult};
+use ggez::event::{KeyCode, KeyMods, MouseButton};
+use ggez::graphics::{self, Color};
+use log::{debug, error, info};
+
+use crate::*;
+use crate::puzzle::empty_cases;
+
+/// Name of the file of the exported position in the data directory
+pub const POSITION_FILE_NAME: &str = "position.txt";
+
+/// Number of moves shown by the analysis of the editor
+const ANALYSIS_MOVES: usize = 8;
+
+/// The editor of a position
+#[derive(Debug, Clone)]
+pub struct Editor {
+    board: Board,
+    /// The side to move
+    turn: Piece,
+    /// The cell chosen with the keyboard
+    cursor: Option<GridPosition>,
+    /// The message of the last action (error, analysis or export)
+    message: Option<String>,
+    theme: Theme,
+}
+
+/// Error of a position that cannot be played
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EditorError {
+    /// There is no disc of a color
+    NoDisc(Piece),
+    /// The side to move cannot play, but the other one can
+    CannotPlay(Piece),
+    /// No player can play, the game is over
+    GameOver,
+}
+
+impl Editor {
+    /// Edit a position
+    pub fn new(board: Board, turn: Piece, theme: Theme) -> Self {
+        Editor { board, turn, cursor: None, message: None, theme }
+    }
+
+    /// Return the edited board
+    pub fn board(&self) -> &Board {
+        &self.board
+    }
+
+    /// Return the side to move
+    pub fn turn(&self) -> Piece {
+        self.turn
+    }
+
+    /// Change a case to the next piece: empty, black, white then empty again
+    ///
+    /// With `forward` false the order is reversed.
+    pub fn cycle(&mut self, position: GridPosition, forward: bool) {
+        let piece = match (self.board.get(position), forward) {
+            (Piece::EMPTY, true) | (Piece::WHITE, false) => Piece::BLACK,
+            (Piece::BLACK, true) | (Piece::EMPTY, false) => Piece::WHITE,
+            _ => Piece::EMPTY,
+        };
+        self.board.set(position, piece);
+        self.message = None;
+    }
+
+    /// Change the side to move
+    pub fn switch_turn(&mut self) {
+        self.turn = self.turn.next();
+        self.message = None;
+    }
+
+    /// Remove every disc
+    pub fn clear(&mut self) {
+        self.board = Board::new(self.board.size());
+        self.message = None;
+    }
+
+    /// Put back the start position of a game
+    pub fn reset(&mut self) {
+        self.board.reset();
+        self.turn = Piece::BLACK;
+        self.message = None;
+    }
+
+    /// Verify if a game can start from the position: both colors are on the board
+    /// and the side to move have a valid move
+    pub fn validate(&self) -> Result<(), EditorError> {
+        for piece in [Piece::BLACK, Piece::WHITE] {
+            if self.board.score(piece) == 0 {
+                return Err(EditorError::NoDisc(piece));
+            }
+        }
+        if self.board.is_finish() {
+            return Err(EditorError::GameOver);
+        }
+        if !self.board.can_play(self.turn) {
+            return Err(EditorError::CannotPlay(self.turn));
+        }
+        Ok(())
+    }
+
+    /// Write the position as a position string
+    pub fn to_position_string(&self) -> String {
+        self.board.to_position_string(self.turn)
+    }
+
+    /// Describe the best moves of the side to move with the predicted final disc difference
+    ///
+    /// The end of a game is solved exactly, before the engine estimate the difference.
+    pub fn analyze(&self) -> Result<String, EditorError> {
+        self.validate()?;
+        let exact = empty_cases(&self.board) <= DEFAULT_PUZZLE_EMPTY_CASES;
+        let mut moves = if exact {
+            Engine::solve(&self.board, self.turn)
+        } else {
+            let engine = Engine::new(DEFAULT_ANALYSIS_DEPTH);
+            self.board.get_valid_moves(self.turn)
+                .into_iter()
+                .map(|position| {
+                    let mut board = self.board.clone();
+                    board.update(position, self.turn);
+                    (position, -engine.predict_difference(&board, self.turn.next()))
+                })
+                .collect()
+        };
+        moves.sort_by_key(|(_, difference)| -difference);
+
+        let mut text = format!("Best moves of {} ({})", self.turn, if exact { "exact" } else { "estimated" });
+        for (position, difference) in moves.into_iter().take(ANALYSIS_MOVES) {
+            text += &format!("\n{}: {:+}", position.to_notation(), difference);
+        }
+        Ok(text)
+    }
+
+    /// Describe the game to play from the position, with the players of the setup menu
+    pub fn game(&self, world: &World) -> Result<SavedGame, EditorError> {
+        self.validate()?;
+        let (width, height) = self.board.size();
+        Ok(SavedGame {
+            moves: String::new(),
+            position: Some(self.to_position_string()),
+            board: BoardConfig { width, height },
+            ..GameSetup { start: StartPosition::Standard, ..world.setup.clone() }.game(&world.config)
+        })
+    }
+
+    /// Save the position string in the data directory, return the message to show
+    fn export(&self) -> String {
+        let position = self.to_position_string();
+        info!("POSITION {}", position);
+        let path = match SavedGame::data_path(POSITION_FILE_NAME) {
+            Some(path) => path,
+            None => return position,
+        };
+        let result = path.parent().map_or(Ok(()), fs::create_dir_all)
+            .and_then(|_| fs::write(&path, format!("{}\n", position)));
+        match result {
+            Ok(()) => format!("{}\nSaved in {}", position, path.display()),
+            Err(err) => {
+                error!("Cannot save the position in {}: {}", path.display(), err);
+                position
+            }
+        }
+    }
+
+    /// Start a game from the position
+    fn play(&mut self, world: &World) -> Transition {
+        let game = self.game(world).map_err(|err| err.to_string())
+            .and_then(|game| start_game(world, &game));
+        match game {
+            Ok(game) => {
+                info!("PLAY from the position {}", self.to_position_string());
+                Transition::Push(Box::new(game))
+            }
+            Err(err) => {
+                self.message = Some(err);
+                Transition::None
+            }
+        }
+    }
+
+    /// Move the keyboard cursor, it appears in the middle of the board the first time
+    fn move_cursor(&mut self, dx: i16, dy: i16) {
+        let (width, height) = self.board.size();
+        self.cursor = Some(match self.cursor {
+            Some(cursor) => GridPosition::new((cursor.x + dx).clamp(0, width - 1), (cursor.y + dy).clamp(0, height - 1)),
+            None => GridPosition::new(width / 2 - 1, height / 2 - 1),
+        });
+    }
+
+    /// Return the text of the panel
+    fn panel_text(&self) -> String {
+        let mut text = format!("Board editor\n\n{} to play\nBLACK: {}  WHITE: {}\n\n",
+            self.turn, self.board.score(Piece::BLACK), self.board.score(Piece::WHITE));
+        text += "Click / Space: change a case\nTab: change the side to move\nC: clear  I: start position\n\
+            Enter: play from here\nA: analyze  E: export";
+        if let Some(message) = &self.message {
+            text += &format!("\n\n{}", message);
+        }
+        text
+    }
+}
+
+impl Scene for Editor {
+    /// Draw the board (with the valid moves of the side to move) and the panel
+    fn draw(&mut self, ctx: &mut Context, _world: &World) -> GameResult {
+        graphics::clear(ctx, self.theme.background_color);
+        let layout = Layout::from_context(ctx);
+        self.board.draw_animated(ctx, layout.board, self.turn, &self.theme, None)?;
+        if let Some(cursor) = self.cursor {
+            self.board.draw_cursor(ctx, &self.theme, cursor, layout.board)?;
+        }
+
+        let area = layout.panel;
+        let margin = 10.0 * layout.scale;
+        let background = graphics::MeshBuilder::new()
+            .rectangle(graphics::DrawMode::fill(), area, Color::new(0.0, 0.0, 0.0, 0.6))?
+            .build(ctx)?;
+        graphics::draw(ctx, &background, graphics::DrawParam::default())?;
+        let font = graphics::Font::new(ctx, self.theme.font_path.as_ref())?;
+        let mut text = graphics::Text::new((self.panel_text(), font, layout.font_size(self.theme.font_scale)));
+        text.set_bounds(glam::Vec2::new(area.w - 2.0 * margin, area.h - 2.0 * margin), graphics::Align::Left);
+        graphics::draw(ctx, &text, (glam::Vec2::new(area.x + margin, area.y + margin), Color::WHITE))
+    }
+
+    /// Change the clicked case, forward with the left button and backward with the right one
+    fn mouse_down(&mut self, ctx: &mut Context, _world: &mut World, button: MouseButton, x: f32, y: f32) -> Transition {
+        let mut layout = Layout::from_context(ctx);
+        layout.board = self.theme.grid_area(layout.board);
+        match layout.board_position(x, y) {
+            Some(position) => {
+                let position = position.into_grid(self.board.cell_size((layout.board.w, layout.board.h)));
+                self.cycle(position, button != MouseButton::Right);
+            }
+            None => debug!("The click ({}, {}) is not on the board", x, y),
+        }
+        Transition::None
+    }
+
+    /// Called every time a key gets pressed
+    ///
+    /// - Arrows: move the cursor
+    /// - Space: change the case of the cursor (Shift: backward)
+    /// - Tab: change the side to move
+    /// - C / I: clear the board / start position
+    /// - Enter: play from the position
+    /// - A: analyze the position
+    /// - E: export the position string
+    /// - Escape: back to the menu
+    fn key_down(&mut self, _ctx: &mut Context, world: &mut World, keycode: KeyCode, keymod: KeyMods) -> Transition {
+        match keycode {
+            KeyCode::Escape => {info!("LEAVE the editor from key Escape"); return Transition::Pop;},
+            KeyCode::Up => self.move_cursor(0, -1),
+            KeyCode::Down => self.move_cursor(0, 1),
+            KeyCode::Left => self.move_cursor(-1, 0),
+            KeyCode::Right => self.move_cursor(1, 0),
+            KeyCode::Space => match self.cursor {
+                Some(cursor) => self.cycle(cursor, !keymod.contains(KeyMods::SHIFT)),
+                None => self.move_cursor(0, 0),
+            },
+            KeyCode::Tab => self.switch_turn(),
+            KeyCode::C => self.clear(),
+            KeyCode::I => self.reset(),
+            KeyCode::Return | KeyCode::NumpadEnter => return self.play(world),
+            KeyCode::A => self.message = Some(self.analyze().unwrap_or_else(|err| err.to_string())),
+            KeyCode::E => self.message = Some(self.export()),
+            _ => {}
+        }
+        Transition::None
+    }
+}
+
+impl fmt::Display for EditorError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            EditorError::NoDisc(piece) => write!(f, "There is no {} disc", piece),
+            EditorError::CannotPlay(piece) => write!(f, "{} cannot play, change the side to move", piece),
+            EditorError::GameOver => write!(f, "No player can play, the game is over"),
+        }
+    }
+}
+
+impl Error for EditorError {}
+
+#[allow(unreachable_code)]
+#[cfg(test)]
+mod tests {
+    use crate::*;
+
+    #[test]
+    fn cycle() {
+        let mut editor = Editor::new(Board::new((4, 4)), Piece::BLACK, Theme::default());
+        let position = GridPosition::new(1, 2);
+        editor.cycle(position, true);
+        assert_eq!(editor.board().get(position), Piece::BLACK);
+        editor.cycle(position, true);
+        assert_eq!(editor.board().get(position), Piece::WHITE);
+        editor.cycle(position, true);
+        assert_eq!(editor.board().get(position), Piece::EMPTY);
+        editor.cycle(position, false);
+        assert_eq!(editor.board().get(position), Piece::WHITE);
+
+        editor.switch_turn();
+        assert_eq!(editor.to_position_string(), "---------O------ O");
+        editor.reset();
+        assert_eq!(editor.to_position_string(), "-----OX--XO----- X");
+        editor.clear();
+        assert_eq!(editor.board().score(Piece::BLACK), 0);
+    }
+
+    #[test]
+    fn validate() {
+        let editor = |position: &str| {
+            let (board, turn) = Board::from_position_string(position, None).unwrap();
+            Editor::new(board, turn, Theme::default())
+        };
+        assert_eq!(editor("-----OX--XO----- X").validate(), Ok(()));
+        assert_eq!(editor("-----XX--XX----- X").validate(), Err(EditorError::NoDisc(Piece::WHITE)));
+        assert_eq!(editor("O-X------------- X").validate(), Err(EditorError::GameOver));
+        assert_eq!(editor("--OX------------ O").validate(), Err(EditorError::CannotPlay(Piece::WHITE)));
+        assert!(editor("--OX------------ O").analyze().is_err());
+        assert_eq!(editor("--OX------------ X").validate(), Ok(()));
+    }
+
+    #[test]
+    fn analyze() {
+        // Black have two moves, a1 (win by 2) and d4 (win by 10), see the test of `Engine::solve`
+        let (board, turn) = Board::from_position_string("-OXX OXXX OXOX OXX- X", None).unwrap();
+        let editor = Editor::new(board, turn, Theme::default());
+        assert_eq!(editor.analyze().unwrap(), "Best moves of BLACK (exact)\nd4: +10\na1: +2");
+
+        let editor = Editor::new(Board::default(), Piece::BLACK, Theme::default());
+        assert!(editor.analyze().unwrap().starts_with("Best moves of BLACK (estimated)\n"));
+    }
+}
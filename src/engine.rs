@@ -0,0 +1,349 @@
+//! A small alpha-beta engine used by the computer players
+
+use std::time::{Duration, Instant};
+
+use crate::*;
+
+/// The strongest level a computer player can have
+pub const MAX_AI_LEVEL: u8 = 5;
+
+/// The deepest search of an engine limited by time
+pub const MAX_SEARCH_DEPTH: u8 = 60;
+
+/// Score given to a won position, the final disc difference is added to it
+const WIN_SCORE: i32 = 10_000;
+
+/// Points of the heuristic that are worth about one disc at the end of the game
+const HEURISTIC_PER_DISC: i32 = 2;
+
+/// Simple engine that search the best move with a negamax (alpha-beta pruning)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Engine {
+    depth: u8,
+    time_limit: Option<Duration>,
+}
+
+/// State of a running search
+struct Search {
+    deadline: Option<Instant>,
+    timeout: bool,
+}
+
+impl Engine {
+    /// Create an engine that look `depth` moves ahead (at least 1)
+    pub fn new(depth: u8) -> Self {
+        Engine { depth: depth.max(1), time_limit: None }
+    }
+
+    /// Create an engine that search deeper and deeper until `time_limit` is elapsed
+    ///
+    /// The search stop at [`MAX_SEARCH_DEPTH`] even if there is some time left.
+    pub fn with_time_limit(time_limit: Duration) -> Self {
+        Engine { depth: MAX_SEARCH_DEPTH, time_limit: Some(time_limit) }
+    }
+
+    /// Create the engine of a computer player level (1 to [`MAX_AI_LEVEL`])
+    pub fn from_level(level: u8) -> Self {
+        Engine::new(level.clamp(1, MAX_AI_LEVEL))
+    }
+
+    /// Return how many moves the engine look ahead (the maximum if it is limited by time)
+    pub fn depth(&self) -> u8 {
+        self.depth
+    }
+
+    /// Return the time the engine can use to search a move
+    pub fn time_limit(&self) -> Option<Duration> {
+        self.time_limit
+    }
+
+    /// Search the best move for a player, `None` if the player cannot play
+    pub fn best_move(&self, board: &Board, player_piece: Piece) -> Option<GridPosition> {
+        let time_limit = match self.time_limit {
+            None => return Engine::search_move(board, player_piece, self.depth, &mut Search::unlimited()),
+            Some(time_limit) => time_limit,
+        };
+
+        // Iterative deepening: keep the move of the deepest search finished in time
+        let mut search = Search { deadline: Some(Instant::now() + time_limit), timeout: false };
+        let mut best_move = None;
+        for depth in 1..=self.depth {
+            let found_move = Engine::search_move(board, player_piece, depth, &mut search);
+            if search.timeout {
+                break;
+            }
+            best_move = found_move;
+        }
+        // Even without time, a valid move have to be played
+        best_move.or_else(|| board.get_valid_moves(player_piece).first().copied())
+    }
+
+    /// Evaluate the board from the point of view of `player_piece` (who have to play)
+    ///
+    /// An engine limited by time evaluate at the depth of [`MAX_AI_LEVEL`].
+    pub fn evaluate(&self, board: &Board, player_piece: Piece) -> i32 {
+        Engine::negamax(board, player_piece, self.depth.min(MAX_AI_LEVEL), -i32::MAX, i32::MAX, &mut Search::unlimited())
+    }
+
+    /// Predict the final disc difference (the discs of `player_piece` minus the other ones)
+    ///
+    /// It is exact when the search reach the end of the game, else it is estimated from
+    /// the heuristic and it stay between minus and plus the number of cases.
+    pub fn predict_difference(&self, board: &Board, player_piece: Piece) -> i32 {
+        let (width, height) = board.size();
+        let cases = width as i32 * height as i32;
+        match self.evaluate(board, player_piece) {
+            score if score.abs() > WIN_SCORE / 2 => Engine::final_difference(score),
+            score => (score / HEURISTIC_PER_DISC).clamp(-cases, cases),
+        }
+    }
+
+    /// Solve the end of a game: the exact final disc difference after each valid move of a player
+    ///
+    /// The search go to the end of the game, it is slow with more than about 14 empty cases.
+    pub fn solve(board: &Board, player_piece: Piece) -> Vec<(GridPosition, i32)> {
+        let (width, height) = board.size();
+        let empty = width as i32 * height as i32 - board.score(Piece::BLACK) as i32 - board.score(Piece::WHITE) as i32;
+        // Each move fill a case, so the search always reach the end of the game
+        let depth = empty.clamp(1, u8::MAX as i32) as u8;
+        Engine::new(depth)
+            .evaluate_moves_at(board, player_piece, depth)
+            .into_iter()
+            .map(|(position, score)| (position, Engine::final_difference(score)))
+            .collect()
+    }
+
+    /// Evaluate every valid move of a player, the scores are from the point of view of `player_piece`
+    ///
+    /// Unlike [`Engine::best_move`] each score is exact (at the depth of [`Engine::evaluate`]),
+    /// so the moves can be compared.
+    pub fn evaluate_moves(&self, board: &Board, player_piece: Piece) -> Vec<(GridPosition, i32)> {
+        self.evaluate_moves_at(board, player_piece, self.depth.min(MAX_AI_LEVEL))
+    }
+
+    /// Evaluate every valid move of a player at a depth
+    fn evaluate_moves_at(&self, board: &Board, player_piece: Piece, depth: u8) -> Vec<(GridPosition, i32)> {
+        board.get_valid_moves(player_piece)
+            .into_iter()
+            .map(|position| {
+                let mut next_board = board.clone();
+                next_board.update(position, player_piece);
+                let score = -Engine::negamax(&next_board, player_piece.next(), depth - 1, -i32::MAX, i32::MAX, &mut Search::unlimited());
+                (position, score)
+            })
+            .collect()
+    }
+
+    /// Search the best move at a fixed depth
+    fn search_move(board: &Board, player_piece: Piece, depth: u8, search: &mut Search) -> Option<GridPosition> {
+        let mut best_move = None;
+        let mut alpha = -i32::MAX;
+        for position in board.get_valid_moves(player_piece) {
+            let mut next_board = board.clone();
+            next_board.update(position, player_piece);
+            let score = -Engine::negamax(&next_board, player_piece.next(), depth - 1, -i32::MAX, -alpha, search);
+            if search.timeout {
+                return None;
+            }
+            if best_move.is_none() || score > alpha {
+                alpha = score;
+                best_move = Some(position);
+            }
+        }
+        best_move
+    }
+
+    /// Negamax search of the board, the score is for `player_piece` who have to play
+    fn negamax(board: &Board, player_piece: Piece, depth: u8, mut alpha: i32, beta: i32, search: &mut Search) -> i32 {
+        if search.is_timeout() {
+            return 0;
+        }
+        let valid_moves = board.get_valid_moves(player_piece);
+        if valid_moves.is_empty() {
+            if !board.can_play(player_piece.next()) {
+                return Engine::final_score(board, player_piece);
+            }
+            // The player pass
+            return -Engine::negamax(board, player_piece.next(), depth, -beta, -alpha, search);
+        }
+        if depth == 0 {
+            return Engine::heuristic(board, player_piece);
+        }
+        for position in valid_moves {
+            let mut next_board = board.clone();
+            next_board.update(position, player_piece);
+            let score = -Engine::negamax(&next_board, player_piece.next(), depth - 1, -beta, -alpha, search);
+            if score >= beta {
+                return score;
+            }
+            alpha = alpha.max(score);
+        }
+        alpha
+    }
+
+    /// Score of a finished game: a win is always better than any heuristic
+    fn final_score(board: &Board, player_piece: Piece) -> i32 {
+        let difference = board.score(player_piece) as i32 - board.score(player_piece.next()) as i32;
+        match difference {
+            d if d > 0 => WIN_SCORE + d,
+            d if d < 0 => -WIN_SCORE + d,
+            _ => 0,
+        }
+    }
+
+    /// Return the disc difference of a score of a finished game (see [`Engine::final_score`])
+    fn final_difference(score: i32) -> i32 {
+        match score {
+            score if score > 0 => score - WIN_SCORE,
+            score if score < 0 => score + WIN_SCORE,
+            _ => 0,
+        }
+    }
+
+    /// Estimate a board during the game
+    ///
+    /// Corners are the most valuable cases, then the mobility (number of valid moves)
+    /// and finally the number of pieces.
+    fn heuristic(board: &Board, player_piece: Piece) -> i32 {
+        let enemy_piece = player_piece.next();
+        let (width, height) = board.size();
+        let mut corners = 0;
+        for corner in [
+            GridPosition::new(0, 0),
+            GridPosition::new(width - 1, 0),
+            GridPosition::new(0, height - 1),
+            GridPosition::new(width - 1, height - 1),
+        ] {
+            let piece = board.get(corner);
+            if piece == player_piece {
+                corners += 1;
+            } else if piece == enemy_piece {
+                corners -= 1;
+            }
+        }
+        let mobility = board.get_valid_moves(player_piece).len() as i32
+            - board.get_valid_moves(enemy_piece).len() as i32;
+        let discs = board.score(player_piece) as i32 - board.score(enemy_piece) as i32;
+        25 * corners + 5 * mobility + discs
+    }
+}
+
+impl Search {
+    /// A search without time limit
+    fn unlimited() -> Self {
+        Search { deadline: None, timeout: false }
+    }
+
+    /// Verify (and remember) if the time of the search is elapsed
+    fn is_timeout(&mut self) -> bool {
+        if !self.timeout {
+            self.timeout = self.deadline.is_some_and(|deadline| Instant::now() >= deadline);
+        }
+        self.timeout
+    }
+}
+
+#[allow(unreachable_code)]
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use crate::{Board, Engine, GridPosition, Piece};
+    use grid::grid;
+
+    const B: Piece = Piece::BLACK;
+    const W: Piece = Piece::WHITE;
+    const E: Piece = Piece::EMPTY;
+
+    #[test]
+    fn best_move_take_corner() {
+        let grid = grid![[E,W,W,B,E,E]
+                                    [E,E,B,E,E,E]
+                                    [E,E,W,B,E,E]
+                                    [E,E,B,W,E,E]
+                                    [E,E,E,E,E,E]
+                                    [E,E,E,E,E,E]];
+        let board = Board::set_board(grid);
+        assert_eq!(Engine::new(1).best_move(&board, B), Some(GridPosition::new(0, 0)));
+    }
+
+    #[test]
+    fn best_move_cannot_play() {
+        let grid = grid![[B,B,B,B]
+                                    [B,B,B,B]
+                                    [B,B,B,B]
+                                    [B,B,B,E]];
+        let board = Board::set_board(grid);
+        assert_eq!(Engine::new(3).best_move(&board, W), None);
+    }
+
+    #[test]
+    fn evaluate_finished_game() {
+        let grid = grid![[B,B,B,B]
+                                    [B,B,B,B]
+                                    [W,W,W,W]
+                                    [W,W,W,E]];
+        let board = Board::set_board(grid);
+        assert!(Engine::new(2).evaluate(&board, B) > 0);
+        assert!(Engine::new(2).evaluate(&board, W) < 0);
+    }
+
+    #[test]
+    fn best_move_with_time_limit() {
+        let board = Board::default();
+        let engine = Engine::with_time_limit(Duration::from_millis(50));
+        let position = engine.best_move(&board, B).unwrap();
+        assert!(board.is_valid_move(position, B));
+    }
+
+    #[test]
+    fn predict_difference() {
+        let grid = grid![[B,B,B,B]
+                                    [B,B,B,B]
+                                    [W,W,W,W]
+                                    [W,W,W,E]];
+        let board = Board::set_board(grid);
+        // White cannot play at d4, black take it and win 11-5
+        assert_eq!(Engine::new(2).predict_difference(&board, W), -6);
+        assert_eq!(Engine::new(2).predict_difference(&board, B), 6);
+        let difference = Engine::new(1).predict_difference(&Board::default(), B);
+        assert!((-64..=64).contains(&difference));
+    }
+
+    #[test]
+    fn solve() {
+        let grid = grid![[E,W,B,B]
+                                    [W,B,B,B]
+                                    [W,B,W,B]
+                                    [W,B,B,E]];
+        let board = Board::set_board(grid);
+        // After a1 white take d4 (9-7), after d4 white pass and black take a1 (13-3)
+        let mut solution = Engine::solve(&board, B);
+        solution.sort_by_key(|(position, _)| (position.y, position.x));
+        assert_eq!(solution, vec![(GridPosition::new(0, 0), 2), (GridPosition::new(3, 3), 10)]);
+        assert_eq!(Engine::solve(&board, W), vec![(GridPosition::new(0, 0), 4), (GridPosition::new(3, 3), -2)]);
+    }
+
+    #[test]
+    fn evaluate_moves() {
+        let grid = grid![[E,W,W,B,E,E]
+                                    [E,E,B,E,E,E]
+                                    [E,E,W,B,E,E]
+                                    [E,E,B,W,E,E]
+                                    [E,E,E,E,E,E]
+                                    [E,E,E,E,E,E]];
+        let board = Board::set_board(grid);
+        let scores = Engine::new(1).evaluate_moves(&board, B);
+        assert_eq!(scores.len(), board.get_valid_moves(B).len());
+        let best = scores.iter().max_by_key(|(_, score)| *score).unwrap();
+        assert_eq!(best.0, GridPosition::new(0, 0));
+        assert!(Engine::new(2).evaluate_moves(&board, B).iter().all(|(position, _)| board.is_valid_move(*position, B)));
+    }
+
+    #[test]
+    fn from_level() {
+        assert_eq!(Engine::from_level(0).depth(), 1);
+        assert_eq!(Engine::from_level(3).depth(), 3);
+        assert_eq!(Engine::from_level(42).depth(), crate::MAX_AI_LEVEL);
+    }
+}
@@ -0,0 +1,42 @@
+//! Errors raised while playing a move
+
+use std::error::Error;
+use std::fmt;
+
+use crate::*;
+
+/// Error raised when a move cannot be played
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OthelloError {
+    /// The position is out of the board
+    OutOfBounds(GridPosition),
+    /// There is already a piece at this position
+    Occupied(GridPosition),
+    /// The move do not eat any piece of the other player
+    IllegalMove { position: GridPosition, piece: Piece },
+    /// `Piece::EMPTY` cannot play
+    EmptyPiece,
+    /// The game is over, no move can be played
+    GameOver,
+    /// It is not the turn of this player
+    WrongTurn { expected: Piece, found: Piece },
+}
+
+impl fmt::Display for OthelloError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            OthelloError::OutOfBounds(position) => write!(f, "the position {} is out of the board", position),
+            OthelloError::Occupied(position) => write!(f, "the case {} is not empty", position.to_notation()),
+            OthelloError::IllegalMove { position, piece } => {
+                write!(f, "{} cannot play at {}", piece, position.to_notation())
+            }
+            OthelloError::EmptyPiece => write!(f, "EMPTY is not a player"),
+            OthelloError::GameOver => write!(f, "the game is over"),
+            OthelloError::WrongTurn { expected, found } => {
+                write!(f, "{} cannot play, it is the turn of {}", found, expected)
+            }
+        }
+    }
+}
+
+impl Error for OthelloError {}
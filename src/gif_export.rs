@@ -0,0 +1,282 @@
+//! Animated GIF of a whole game written without window, one frame per move
+//!
+//! ```no_run
+//! use rust_othello::*;
+//!
+//! let game = SavedGame { moves: String::from("f5d6c3"), ..SavedGame::default() };
+//! GameGif::new(&game).unwrap()
+//!     .set_theme(Theme::by_name("dark").unwrap())
+//!     .save("game.gif".as_ref())
+//!     .unwrap();
+//! ```
+
+use std::error::Error;
+use std::fmt;
+use std::fs::File;
+use std::io::{self, BufWriter, Write};
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+use ab_glyph::FontVec;
+use glam::Vec2;
+
+use crate::*;
+
+/// Time each move is shown when it is not chosen
+pub const DEFAULT_GIF_DELAY: Duration = Duration::from_secs(1);
+
+/// Number of delays the last frame is shown before the GIF restart
+const LAST_FRAME_DELAYS: u16 = 3;
+
+/// Quality of the reduction of the colors to 256 (1 is the best but the slowest, 30 the fastest)
+const QUANTIZATION_SPEED: i32 = 10;
+
+/// The frames of a game: the start position then the board after each move
+#[derive(Debug, Clone)]
+pub struct GameGif {
+    /// The board before the first move then after each move
+    boards: Vec<Board>,
+    /// The played moves and who played them
+    moves: Vec<(GridPosition, Piece)>,
+    theme: Theme,
+    cell_size: f32,
+    /// Time each frame is shown
+    delay: Duration,
+}
+
+/// Error raised while writing a GIF
+#[derive(Debug)]
+pub enum GifError {
+    /// The file cannot be written
+    Io(PathBuf, io::Error),
+    /// The font of the theme cannot be loaded
+    Font(String, io::Error),
+    /// The frames cannot be encoded
+    Encode(gif::EncodingError),
+    /// The frames (width, height) are larger than 65535 pixels
+    TooLarge(usize, usize),
+}
+
+impl GameGif {
+    /// Replay a saved game, the theme of the game is used if it has one
+    pub fn new(game: &SavedGame) -> Result<Self, NotationError> {
+        let (mut board, mut turn) = game.start()?;
+        let mut boards = vec![board.clone()];
+        let mut moves = Vec::new();
+        for (index, position) in game.moves()?.into_iter().enumerate() {
+            let piece = play_transcript_move(&mut board, turn, index, position)?;
+            boards.push(board.clone());
+            moves.push((position, piece));
+            turn = piece.next();
+        }
+        let theme = game.theme.as_ref().map(ThemeConfig::theme).unwrap_or_default();
+        Ok(GameGif { boards, moves, theme, cell_size: DEFAULT_DIAGRAM_CELL_SIZE, delay: DEFAULT_GIF_DELAY })
+    }
+
+    /// Set the theme of the frames
+    pub fn set_theme(mut self, theme: Theme) -> Self {
+        self.theme = theme;
+        self
+    }
+
+    /// Set the size of a cell (in pixel)
+    pub fn set_cell_size(mut self, cell_size: f32) -> Self {
+        self.cell_size = cell_size;
+        self
+    }
+
+    /// Set the time each move is shown, the last frame is shown longer
+    pub fn set_delay(mut self, delay: Duration) -> Self {
+        self.delay = delay;
+        self
+    }
+
+    /// Return the number of frames: the start position and one per move
+    pub fn len(&self) -> usize {
+        self.boards.len()
+    }
+
+    /// Verify if there is no frame, never true
+    pub fn is_empty(&self) -> bool {
+        self.boards.is_empty()
+    }
+
+    /// Return the diagram of a frame (the board with its last move marked)
+    fn diagram(&self, index: usize) -> Diagram {
+        let last_move = index.checked_sub(1).map(|index| self.moves[index].0);
+        Diagram::new(&self.boards[index])
+            .set_theme(self.theme.clone())
+            .set_cell_size(self.cell_size)
+            .set_last_move(last_move)
+    }
+
+    /// Describe a frame with the score, e.g. "3/60 BLACK c3: 4-1"
+    ///
+    /// The result is written on the last frame when the game is over.
+    pub fn caption(&self, index: usize) -> String {
+        let board = &self.boards[index];
+        let is_over = !board.can_play(Piece::BLACK) && !board.can_play(Piece::WHITE);
+        if index + 1 == self.boards.len() && is_over {
+            return Outcome::new(board, EndReason::NoMoreMoves).to_string();
+        }
+        let score = format!("{}-{}", board.score(Piece::BLACK), board.score(Piece::WHITE));
+        match index {
+            0 => format!("Start: {}", score),
+            _ => {
+                let (position, piece) = self.moves[index - 1];
+                format!("{}/{} {} {}: {}", index, self.moves.len(), piece, position.to_notation(), score)
+            }
+        }
+    }
+
+    /// Return the size of the frames (width, height) in pixels
+    pub fn frame_size(&self) -> (usize, usize) {
+        let (width, height) = self.diagram(0).size();
+        (width.ceil() as usize, (height + 0.8 * self.cell_size).ceil() as usize)
+    }
+
+    /// Draw a frame: the diagram with the caption under it
+    pub fn frame(&self, index: usize, font: &FontVec) -> Raster {
+        let diagram = self.diagram(index);
+        let (width, height) = diagram.size();
+        let caption_height = 0.8 * self.cell_size;
+        let (raster_width, raster_height) = self.frame_size();
+        let mut raster = Raster::new(raster_width, raster_height, self.theme.background_color);
+        diagram.draw_raster(&mut raster, font);
+
+        // The caption is made smaller if it is too long
+        let caption = self.caption(index);
+        let mut size = 0.4 * self.cell_size;
+        let text_width = text_width(font, &caption, size);
+        if text_width > 0.95 * width {
+            size *= 0.95 * width / text_width;
+        }
+        let color = self.theme.coordinates_color.unwrap_or(self.theme.grid_color);
+        raster.text(font, &caption, Vec2::new(width / 2.0, height + caption_height / 2.0), size, color);
+        raster
+    }
+
+    /// Write the GIF, it is played in a loop
+    pub fn write<W: Write>(&self, writer: W) -> Result<(), GifError> {
+        // Every frame have the same size, it is verified before drawing them
+        let (width, height) = match self.frame_size() {
+            (width, height) if width <= u16::MAX as usize && height <= u16::MAX as usize => (width as u16, height as u16),
+            (width, height) => return Err(GifError::TooLarge(width, height)),
+        };
+        let font = load_font(&self.theme.font_path).map_err(|err| GifError::Font(self.theme.font_path.to_string(), err))?;
+        // Hundredths of second
+        let delay = (self.delay.as_millis() / 10).clamp(1, u16::MAX as u128) as u16;
+        let mut encoder = gif::Encoder::new(BufWriter::new(writer), width, height, &[])?;
+        encoder.set_repeat(gif::Repeat::Infinite)?;
+        for index in 0..self.len() {
+            let mut raster = self.frame(index, &font);
+            let mut frame = gif::Frame::from_rgba_speed(width, height, raster.pixels_mut(), QUANTIZATION_SPEED);
+            frame.delay = if index + 1 == self.len() { delay.saturating_mul(LAST_FRAME_DELAYS) } else { delay };
+            encoder.write_frame(&frame)?;
+        }
+        encoder.into_inner().and_then(|mut writer| writer.flush()).map_err(gif::EncodingError::from)?;
+        Ok(())
+    }
+
+    /// Write the GIF in a file
+    pub fn save(&self, path: &Path) -> Result<(), GifError> {
+        let file = File::create(path).map_err(|err| GifError::Io(path.to_path_buf(), err))?;
+        self.write(file).map_err(|err| match err {
+            GifError::Encode(gif::EncodingError::Io(err)) => GifError::Io(path.to_path_buf(), err),
+            err => err,
+        })
+    }
+}
+
+impl fmt::Display for GifError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            GifError::Io(path, err) => write!(f, "cannot write {}: {}", path.display(), err),
+            GifError::Font(path, err) => write!(f, "cannot load the font {}: {}", path, err),
+            GifError::Encode(err) => write!(f, "cannot write the GIF: {}", err),
+            GifError::TooLarge(width, height) =>
+                write!(f, "the GIF cannot be larger than 65535x65535 pixels (found {}x{})", width, height),
+        }
+    }
+}
+
+impl Error for GifError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            GifError::Io(_, err) | GifError::Font(_, err) => Some(err),
+            GifError::Encode(err) => Some(err),
+            GifError::TooLarge(_, _) => None,
+        }
+    }
+}
+
+impl From<gif::EncodingError> for GifError {
+    fn from(err: gif::EncodingError) -> Self {
+        GifError::Encode(err)
+    }
+}
+
+impl From<GifError> for ggez::GameError {
+    fn from(err: GifError) -> Self {
+        ggez::GameError::CustomError(err.to_string())
+    }
+}
+
+#[allow(unreachable_code)]
+#[cfg(test)]
+mod tests {
+    use crate::*;
+
+    #[test]
+    fn frames() {
+        let game = SavedGame { moves: String::from("f5d6"), ..SavedGame::default() };
+        let theme = Theme { coordinates_color: None, ..Theme::default() };
+        let gif = GameGif::new(&game).unwrap().set_theme(theme).set_cell_size(20.0);
+        assert_eq!(gif.len(), 3);
+        assert_eq!(gif.caption(0), "Start: 2-2");
+        assert_eq!(gif.caption(2), "2/2 WHITE d6: 3-3");
+
+        let font = load_font(DEFAULT_THEME.font_path.as_ref()).unwrap();
+        let frame = gif.frame(1, &font);
+        // The board and the caption under it
+        assert_eq!(frame.size(), (160, 176));
+        // The last move f5 is marked on its black disc
+        assert_eq!(frame.get(110, 90), DEFAULT_THEME.last_move_color);
+        assert_eq!(frame.get(70, 70), Some(DEFAULT_THEME.piece_colors.1));
+        assert_eq!(frame.get(110, 96), Some(DEFAULT_THEME.piece_colors.0));
+        assert_eq!(frame.get(2, 2), Some(DEFAULT_THEME.background_color));
+        assert_ne!(gif.frame(0, &font), frame);
+    }
+
+    #[test]
+    fn game_over() {
+        let game = SavedGame {
+            position: Some(String::from("XXXXXXXXXXXXXXOO X")),
+            board: BoardConfig { width: 4, height: 4 },
+            ..SavedGame::default()
+        };
+        let gif = GameGif::new(&game).unwrap();
+        assert_eq!(gif.len(), 1);
+        assert_eq!(gif.caption(0), "BLACK win 14-2");
+    }
+
+    #[test]
+    fn write() {
+        let game = SavedGame { moves: String::from("f5d6c3"), ..SavedGame::default() };
+        let gif = GameGif::new(&game).unwrap().set_cell_size(10.0);
+        let mut data = Vec::new();
+        gif.write(&mut data).unwrap();
+        assert!(data.starts_with(b"GIF89a"));
+        assert_eq!(data.last(), Some(&0x3B));
+
+        let mut decoder = gif::DecodeOptions::new().read_info(data.as_slice()).unwrap();
+        let mut delays = Vec::new();
+        while let Some(frame) = decoder.read_next_frame().unwrap() {
+            delays.push(frame.delay);
+        }
+        assert_eq!(delays, [100, 100, 100, 300]);
+
+        let gif = GameGif::new(&game).unwrap().set_cell_size(10000.0);
+        assert!(matches!(gif.write(std::io::sink()), Err(GifError::TooLarge(_, _))));
+    }
+}
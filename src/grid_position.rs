@@ -4,8 +4,6 @@ use std::fmt;
 use std::cmp::{max, min};
 use std::ops::{Add, AddAssign, Sub, SubAssign};
 
-use crate::*;
-
 
 /// Simple struct for manage coordinates in the grid
 #[derive(Clone, Copy, PartialEq, Eq, Debug)]
@@ -25,16 +23,18 @@ impl GridPosition {
         GridPosition { x: x as i16, y: y as i16 }
     }
 
-    /// Verify if the GridPosition is in the screen
-    pub fn in_screen(&self) -> bool {
-        0 < self.x && self.x < SCREEN_SIZE.0 as i16 &&
-            0 < self.y && self.y < SCREEN_SIZE.1 as i16
+    /// Verify if the GridPosition is in the screen of size `screen_size` (in pixel)
+    pub fn in_screen(&self, screen_size: (f32, f32)) -> bool {
+        0 <= self.x && (self.x as f32) < screen_size.0 &&
+            0 <= self.y && (self.y as f32) < screen_size.1
     }
 
     /// Convert the ScreenPosition (pixel) into GridPosition
-    pub fn into_grid(self) -> Self {
-        let x = self.x / GRID_CELL_SIZE.0;
-        let y = self.y / GRID_CELL_SIZE.1;
+    ///
+    /// The `cell_size` (in pixel) is given by [`Board::cell_size`](crate::Board::cell_size)
+    pub fn into_grid(self, cell_size: (f32, f32)) -> Self {
+        let x = (self.x as f32 / cell_size.0) as i16;
+        let y = (self.y as f32 / cell_size.1) as i16;
         GridPosition { x, y }
     }
 
@@ -99,6 +99,24 @@ mod tests {
         assert_eq!("(10,5)", format!("{}", pos2).as_str());
     }
 
+    #[test]
+    fn in_screen() {
+        assert!(GridPosition::new(0, 0).in_screen((800.0, 600.0)));
+        assert!(GridPosition::new(799, 599).in_screen((800.0, 600.0)));
+        assert!( ! GridPosition::new(800, 0).in_screen((800.0, 600.0)));
+        assert!( ! GridPosition::new(0, 600).in_screen((800.0, 600.0)));
+        assert!( ! GridPosition::new(-1, 10).in_screen((800.0, 600.0)));
+    }
+
+    #[test]
+    fn into_grid() {
+        // 6x6 board on a 600x600 screen
+        let cell_size = (100.0, 100.0);
+        assert_eq!(GridPosition::new(0, 0).into_grid(cell_size), GridPosition::new(0, 0));
+        assert_eq!(GridPosition::new(99, 100).into_grid(cell_size), GridPosition::new(0, 1));
+        assert_eq!(GridPosition::new(599, 250).into_grid(cell_size), GridPosition::new(5, 2));
+    }
+
     #[test]
     fn add() {
         assert_eq!(
@@ -0,0 +1,110 @@
+//! Play a game in a terminal, without window
+//!
+//! The board is written after each move, the moves of the human players are read
+//! one per line (e.g. `f5`).
+
+use std::io::{self, BufRead, Write};
+
+use crate::*;
+
+/// Play a game from `board` where `turn` have to play
+///
+/// The game stop at the end or when the input is closed.
+pub fn run<R: BufRead, W: Write>(
+    mut board: Board,
+    mut turn: Piece,
+    player_black: &Player,
+    player_white: &Player,
+    mut input: R,
+    mut output: W,
+) -> io::Result<()> {
+    loop {
+        writeln!(output, "{}\n", board)?;
+        if board.is_finish() {
+            break;
+        }
+
+        let player = if turn == Piece::WHITE { player_white } else { player_black };
+        if !board.can_play(turn) {
+            writeln!(output, "{} pass", player)?;
+            turn = turn.next();
+            continue;
+        }
+
+        let position = match player.kind {
+            PlayerKind::Computer(engine) => match engine.best_move(&board, turn) {
+                Some(position) => position,
+                None => continue,
+            },
+            PlayerKind::Human => match read_move(&board, player, &mut input, &mut output)? {
+                Some(position) => position,
+                None => return Ok(()),
+            },
+        };
+        writeln!(output, "{} play {}", player, position.to_notation())?;
+        board.update(position, turn);
+        turn = turn.next();
+    }
+
+    let (score_black, score_white) = (board.score(Piece::BLACK), board.score(Piece::WHITE));
+    writeln!(output, "{}: {}\n{}: {}", player_black, score_black, player_white, score_white)?;
+    match score_black.cmp(&score_white) {
+        std::cmp::Ordering::Greater => writeln!(output, "{} win", player_black),
+        std::cmp::Ordering::Less => writeln!(output, "{} win", player_white),
+        std::cmp::Ordering::Equal => writeln!(output, "Draw"),
+    }
+}
+
+/// Ask a valid move to a human player, `None` if the input is closed
+fn read_move<R: BufRead, W: Write>(
+    board: &Board,
+    player: &Player,
+    input: &mut R,
+    output: &mut W,
+) -> io::Result<Option<GridPosition>> {
+    loop {
+        write!(output, "{} to play: ", player)?;
+        output.flush()?;
+        let mut line = String::new();
+        if input.read_line(&mut line)? == 0 {
+            return Ok(None);
+        }
+        match GridPosition::from_notation(line.trim()) {
+            Ok(position) if board.in_board(position) && board.is_valid_move(position, player.piece) => {
+                return Ok(Some(position));
+            }
+            Ok(position) => writeln!(output, "The move {} is not valid", position.to_notation())?,
+            Err(err) => writeln!(output, "{}", err)?,
+        }
+    }
+}
+
+#[allow(unreachable_code)]
+#[cfg(test)]
+mod tests {
+    use crate::*;
+
+    #[test]
+    fn computers_play_until_the_end() {
+        let player_black = Player::computer("Black", Piece::BLACK, 1);
+        let player_white = Player::computer("White", Piece::WHITE, 2);
+        let mut output = Vec::new();
+        headless::run(Board::with_size((4, 4)), Piece::BLACK, &player_black, &player_white,
+                      &b""[..], &mut output).unwrap();
+        let output = String::from_utf8(output).unwrap();
+        assert!(output.ends_with(" win\n") || output.ends_with("Draw\n"));
+    }
+
+    #[test]
+    fn human_moves_are_read() {
+        let player_black = Player::new("Human", Piece::BLACK);
+        let player_white = Player::computer("Computer", Piece::WHITE, 1);
+        let mut output = Vec::new();
+        headless::run(Board::default(), Piece::BLACK, &player_black, &player_white,
+                      &b"a1\nf5\n"[..], &mut output).unwrap();
+        let output = String::from_utf8(output).unwrap();
+        assert!(output.contains("The move a1 is not valid"));
+        assert!(output.contains("Human (BLACK) play f5"));
+        assert!(output.contains("Computer (WHITE) play "));
+    }
+}
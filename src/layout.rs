@@ -0,0 +1,111 @@
+//! Position of the board and the side panel in the window
+
+use ggez::Context;
+use ggez::graphics::{self, Rect};
+
+use crate::*;
+
+/// Areas of the window (in pixel)
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Layout {
+    /// Area of the board: a square in the middle of the left part
+    pub board: Rect,
+    /// Area of the side panel, on the right
+    pub panel: Rect,
+    /// Scale of the texts and of the margins, 1 for a window of the default size
+    pub scale: f32,
+}
+
+impl Layout {
+    /// Split a screen of `screen_size` (in pixel) between the board and the side panel
+    ///
+    /// The panel width grow with the height of the screen,
+    /// on a narrow screen the panel take at most half of it.
+    /// The board is the biggest square centred in the rest of the screen.
+    pub fn new(screen_size: (f32, f32)) -> Self {
+        let (width, height) = (screen_size.0.max(1.0), screen_size.1.max(1.0));
+        let panel_width = (PANEL_WIDTH * height / SCREEN_SIZE.1 as f32).min(width / 2.0);
+        let left_width = width - panel_width;
+        let side = left_width.min(height);
+        Layout {
+            board: Rect::new((left_width - side) / 2.0, (height - side) / 2.0, side, side),
+            panel: Rect::new(left_width, 0.0, panel_width, height),
+            scale: side / SCREEN_SIZE.1 as f32,
+        }
+    }
+
+    /// Compute the layout of the current screen of the window
+    pub fn from_context(ctx: &Context) -> Self {
+        let screen = graphics::screen_coordinates(ctx);
+        Layout::new((screen.w, screen.h))
+    }
+
+    /// Return the size of the texts for a theme font size
+    pub fn font_size(&self, font_scale: f32) -> f32 {
+        font_scale * self.scale
+    }
+
+    /// Convert a position of the screen (in pixel) into a position in the board area
+    ///
+    /// `None` if the position is not on the board.
+    pub fn board_position(&self, x: f32, y: f32) -> Option<GridPosition> {
+        if x < self.board.x || y < self.board.y {
+            return None;
+        }
+        let position = GridPosition::from_screen(x - self.board.x, y - self.board.y);
+        position.in_screen((self.board.w, self.board.h)).then_some(position)
+    }
+}
+
+#[allow(unreachable_code)]
+#[cfg(test)]
+mod tests {
+    use ggez::graphics::Rect;
+
+    use crate::*;
+
+    #[test]
+    fn split_screen() {
+        let layout = Layout::new((1050.0, 800.0));
+        assert_eq!((layout.board.w, layout.board.h), (800.0, 800.0));
+        assert_eq!((layout.panel.x, layout.panel.w), (800.0, PANEL_WIDTH));
+
+        let layout = Layout::new((300.0, 300.0));
+        assert_eq!((layout.board.w, layout.board.h), (206.25, 206.25));
+
+        // On a narrow screen the panel take at most half of it
+        let layout = Layout::new((300.0, 800.0));
+        assert_eq!((layout.board.w, layout.panel.w), (150.0, 150.0));
+    }
+
+    #[test]
+    fn resize() {
+        // A wide window: the board is centred on the left of the panel
+        let layout = Layout::new((2100.0, 800.0));
+        assert_eq!(layout.board, Rect::new(525.0, 0.0, 800.0, 800.0));
+        assert_eq!(layout.panel, Rect::new(1850.0, 0.0, 250.0, 800.0));
+        assert_eq!(layout.font_size(15.0), 15.0);
+
+        // A tall window
+        let layout = Layout::new((1050.0, 1200.0));
+        assert_eq!(layout.panel.w, 375.0);
+        assert_eq!(layout.board, Rect::new(0.0, 262.5, 675.0, 675.0));
+
+        // A high DPI window is twice bigger: the texts too
+        let layout = Layout::new((2100.0, 1600.0));
+        assert_eq!((layout.board.w, layout.panel.w), (1600.0, 500.0));
+        assert_eq!(layout.font_size(15.0), 30.0);
+    }
+
+    #[test]
+    fn board_position() {
+        let layout = Layout::new((1050.0, 800.0));
+        assert_eq!(layout.board_position(799.0, 10.0), Some(GridPosition::new(799, 10)));
+        assert_eq!(layout.board_position(800.0, 10.0), None);
+        assert_eq!(layout.board_position(900.0, 10.0), None);
+
+        let layout = Layout::new((2100.0, 800.0));
+        assert_eq!(layout.board_position(525.0, 10.0), Some(GridPosition::new(0, 10)));
+        assert_eq!(layout.board_position(524.5, 10.0), None);
+    }
+}
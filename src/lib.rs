@@ -1,13 +1,56 @@
-pub use board::{Board, Piece};
+pub use arena::{Arena, ArenaResult};
+pub use analysis::{Analysis, AnalysisView, MoveReport, draw_evaluation_graph, ANALYSIS_FILE_NAMES, BLUNDER_ERROR, DEFAULT_ANALYSIS_DEPTH, MAX_MOVE_ERROR};
+pub use animation::MoveAnimation;
+pub use app::App;
+pub use board::{Board, Flips, Piece};
 pub use config::*;
+pub use diagram::{Diagram, DEFAULT_DIAGRAM_CELL_SIZE};
+pub use editor::{Editor, EditorError, POSITION_FILE_NAME};
+pub use engine::{Engine, MAX_AI_LEVEL, MAX_SEARCH_DEPTH};
+pub use error::OthelloError;
+pub use gif_export::{GameGif, GifError, DEFAULT_GIF_DELAY};
 pub use grid_position::GridPosition;
+pub use layout::Layout;
+pub use menu::*;
+pub use notation::*;
+pub use opening::{balanced_opening, balanced_openings, diagonal_position, random_opening, xot_opening, xot_openings, StartPosition, BALANCED_OPENINGS, DEFAULT_RANDOM_MOVES, RANDOM_BALANCE, XOT_OPENINGS};
 pub use othello::Othello;
-pub use player::Player;
+pub use player::{Player, PlayerKind};
+pub use puzzle::{Answer, Puzzle, PuzzleError, PuzzleScene, PuzzleStats, BUILTIN_PUZZLES, DEFAULT_PUZZLE_EMPTY_CASES, PUZZLE_STATS_FILE_NAME};
+pub use raster::{load_font, text_width, Raster};
+pub use replay::Replay;
+pub use save::{Clocks, GameEnd, SaveError, SavedGame};
+pub use scene::{Popup, Scene, Transition, World};
+pub use sound::{Audio, Sound};
+pub use state::{EndReason, GameState, Move, MoveOutcome, Outcome};
 pub use theme::*;
+pub use tutorial::{Attempt, Goal, Lesson, Tutorial, LESSONS};
 
 pub mod othello;
+pub mod analysis;
+pub mod arena;
+pub mod animation;
+pub mod app;
 pub mod board;
 pub mod player;
 pub mod config;
+pub mod diagram;
+pub mod editor;
+pub mod engine;
+pub mod error;
+pub mod gif_export;
 pub mod grid_position;
+pub mod headless;
+pub mod layout;
+pub mod menu;
+pub mod notation;
+pub mod opening;
+pub mod puzzle;
+pub mod raster;
+pub mod replay;
+pub mod save;
+pub mod scene;
+pub mod sound;
+pub mod state;
 pub mod theme;
+pub mod tutorial;
@@ -13,20 +13,385 @@
 
 
 use std::env;
-use std::path::PathBuf;
+use std::fmt::Display;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::process;
+use std::time::Duration;
 
-use env_logger;
+use clap::{Parser, ValueEnum};
 use ggez::{event, GameResult};
+use log::error;
 
 use rust_othello::*;
 
 
+/// Play othello against a friend or against the computer
+///
+/// Without a game on the command line, the main menu is shown.
+#[derive(Debug, Parser)]
+#[command(version, about)]
+struct Args {
+    /// Configuration file [default: config.toml in the configuration directory]
+    #[arg(long, value_name = "FILE")]
+    config: Option<PathBuf>,
+
+    /// Who play the black pieces
+    #[arg(long, value_enum, value_name = "PLAYER")]
+    black: Option<Controller>,
+
+    /// Who play the white pieces
+    #[arg(long, value_enum, value_name = "PLAYER")]
+    white: Option<Controller>,
+
+    /// Number of moves the computer players look ahead
+    #[arg(long, value_name = "DEPTH", value_parser = clap::value_parser!(u8).range(1..=MAX_AI_LEVEL as i64))]
+    depth: Option<u8>,
+
+    /// Thinking time of the computer players for each move (in seconds)
+    #[arg(long, value_name = "SECONDS", value_parser = parse_seconds, conflicts_with = "depth")]
+    time: Option<Duration>,
+
+    /// Time of each player for the whole game (in seconds), a player lose when his time is over
+    #[arg(long, value_name = "SECONDS", value_parser = parse_seconds)]
+    time_limit: Option<Duration>,
+
+    /// Start position: one character per case row by row ('X' black, 'O' white, '-' empty)
+    /// followed by the side to move ('X' or 'O')
+    #[arg(long, value_name = "POSITION", conflicts_with = "load")]
+    position: Option<String>,
+
+    /// Moves to play from the start position, e.g. "f5d6c3"
+    #[arg(long, value_name = "MOVES", conflicts_with = "load")]
+    transcript: Option<String>,
+
+    /// Start of the game: standard, diagonal, xot (an opening of the XOT list), balanced
+    /// (an 8 moves opening chosen by the engine), random or random:N
+    /// (N random moves with a balanced evaluation)
+    #[arg(long, value_name = "START", value_parser = parse_start, conflicts_with_all = ["load", "position", "transcript"])]
+    start: Option<StartPosition>,
+
+    /// Seed of the random starts, the same seed give the same starts
+    #[arg(long, value_name = "SEED")]
+    seed: Option<u64>,
+
+    /// Play this number of starts twice between two engines (with the colors swapped) and print
+    /// the result, without opening a window
+    #[arg(long, value_name = "STARTS", conflicts_with_all = ["load", "resume", "replay", "headless", "position", "transcript"])]
+    arena: Option<usize>,
+
+    /// Depths of the two engines of the arena, e.g. "4,2" [default: 3,3]
+    #[arg(long, value_name = "DEPTHS", requires = "arena", value_delimiter = ',',
+        value_parser = clap::value_parser!(u8).range(1..=MAX_SEARCH_DEPTH as i64))]
+    arena_depths: Vec<u8>,
+
+    /// Resume a saved game, it is saved back in this file with Ctrl+S
+    #[arg(long, value_name = "FILE")]
+    load: Option<PathBuf>,
+
+    /// Resume the game saved when the window was closed
+    #[arg(long, conflicts_with_all = ["load", "position", "transcript", "board_size"])]
+    resume: bool,
+
+    /// Watch a saved game move by move
+    #[arg(long, value_name = "FILE", conflicts_with_all = ["load", "resume", "headless"])]
+    replay: Option<PathBuf>,
+
+    /// Auto-play the replay at this speed (moves per second)
+    #[arg(long, value_name = "SPEED", requires = "replay", value_parser = parse_speed)]
+    replay_speed: Option<f32>,
+
+    /// Theme of the window: classic, dark, high-contrast, color-blind or a TOML/JSON theme file
+    #[arg(long, value_name = "NAME|FILE")]
+    theme: Option<String>,
+
+    /// Show the moves instantly, without animation
+    #[arg(long)]
+    no_animation: bool,
+
+    /// Do not play the sound effects
+    #[arg(long)]
+    mute: bool,
+
+    /// Draw bigger marks of the valid moves, of the last move and of the keyboard cursor
+    #[arg(long)]
+    large_markers: bool,
+
+    /// Size of the board, e.g. "8" or "10x6" (even numbers only)
+    #[arg(long, value_name = "SIZE", value_parser = parse_board_size, conflicts_with = "load")]
+    board_size: Option<(i16, i16)>,
+
+    /// Play in the terminal without opening a window
+    #[arg(long)]
+    headless: bool,
+
+    /// Write a SVG diagram of the last position of the game in this file, without opening a window
+    #[arg(long, value_name = "FILE", conflicts_with_all = ["replay", "headless"])]
+    export_svg: Option<PathBuf>,
+
+    /// Write the number of each move on its disc in the diagram
+    #[arg(long, requires = "export_svg")]
+    move_numbers: bool,
+
+    /// Cases highlighted in the diagram, e.g. "c4,e3"
+    #[arg(long, value_name = "CASES", requires = "export_svg", value_delimiter = ',', value_parser = parse_case)]
+    highlight: Vec<GridPosition>,
+
+    /// Write an animated GIF of the game in this file (one frame per move), without opening a window
+    #[arg(long, value_name = "FILE", conflicts_with_all = ["replay", "headless", "export_svg"])]
+    export_gif: Option<PathBuf>,
+
+    /// Time each move is shown in the GIF (in seconds) [default: 1]
+    #[arg(long, value_name = "SECONDS", requires = "export_gif", value_parser = parse_seconds)]
+    gif_delay: Option<Duration>,
+
+    /// Write the analysis of the game by the engine in this file (JSON if it ends with .json, else text),
+    /// without opening a window
+    #[arg(long, value_name = "FILE", conflicts_with_all = ["replay", "headless", "export_svg", "export_gif"])]
+    analysis: Option<PathBuf>,
+
+    /// Number of moves the engine look ahead to analyze the game [default: 4]
+    #[arg(long, value_name = "DEPTH", requires = "analysis", value_parser = clap::value_parser!(u8).range(1..=MAX_AI_LEVEL as i64))]
+    analysis_depth: Option<u8>,
+
+    /// Train with the puzzles of this file (one position string per line)
+    #[arg(long, value_name = "FILE", conflicts_with_all = ["load", "resume", "replay", "headless"])]
+    puzzles: Option<PathBuf>,
+
+    /// Edit the start position (from --position, --transcript or --load) in the board editor
+    #[arg(long, conflicts_with_all = ["replay", "headless", "puzzles"])]
+    editor: bool,
+
+    /// Print the puzzles found in a file of games (one transcript per line), without opening a window
+    #[arg(long, value_name = "GAMES_FILE", conflicts_with_all = ["load", "resume", "replay", "headless", "puzzles"])]
+    generate_puzzles: Option<PathBuf>,
+
+    /// Most empty cases of the generated puzzles [default: 12]
+    #[arg(long, value_name = "N", requires = "generate_puzzles", value_parser = clap::value_parser!(u8).range(1..=20))]
+    puzzle_empty: Option<u8>,
+}
+
+/// Who choose the moves of a player
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+enum Controller {
+    Human,
+    Computer,
+}
+
+/// Parse a board size like "8" or "10x6"
+fn parse_board_size(size: &str) -> Result<(i16, i16), String> {
+    let (width, height) = size.split_once('x').unwrap_or((size, size));
+    let parse = |n: &str| n.trim().parse::<i16>().map_err(|_| format!("\"{}\" is not a size like \"8\" or \"10x6\"", size));
+    let size = (parse(width)?, parse(height)?);
+    if Board::is_valid_size(size) {
+        Ok(size)
+    } else {
+        Err(String::from("the size of the board must be even, at least 4 and at most 26"))
+    }
+}
+
+/// Parse a case of the board like "f5"
+fn parse_case(case: &str) -> Result<GridPosition, String> {
+    GridPosition::from_notation(case.trim()).map_err(|err| err.to_string())
+}
+
+/// Parse a start like "xot" or "random:8"
+fn parse_start(start: &str) -> Result<StartPosition, String> {
+    start.parse()
+}
+
+/// Parse a positive speed (moves per second)
+fn parse_speed(speed: &str) -> Result<f32, String> {
+    match speed.parse::<f32>() {
+        Ok(speed) if speed > 0.0 && speed.is_finite() => Ok(speed),
+        _ => Err(format!("\"{}\" is not a positive number of moves per second", speed)),
+    }
+}
+
+/// Parse a positive duration in seconds
+fn parse_seconds(seconds: &str) -> Result<Duration, String> {
+    match seconds.parse::<f32>() {
+        Ok(seconds) if seconds > 0.0 && seconds.is_finite() => Ok(Duration::from_secs_f32(seconds)),
+        _ => Err(format!("\"{}\" is not a positive number of seconds", seconds)),
+    }
+}
+
+/// Print an error and stop the program
+fn exit_with_error(err: impl Display) -> ! {
+    error!("{}", err);
+    eprintln!("error: {}", err);
+    process::exit(2)
+}
+
+impl Args {
+    /// Apply the command line to a player
+    fn setup_player(&self, player: &mut Player, controller: Option<Controller>) {
+        match controller {
+            Some(Controller::Human) => player.kind = PlayerKind::Human,
+            Some(Controller::Computer) if player.is_human() => {
+                player.kind = PlayerKind::Computer(Engine::from_level(DEFAULT_AI_LEVEL));
+            }
+            _ => {}
+        }
+        if !player.is_human() {
+            if let Some(time) = self.time {
+                player.kind = PlayerKind::Computer(Engine::with_time_limit(time));
+            } else if let Some(depth) = self.depth {
+                player.kind = PlayerKind::Computer(Engine::new(depth));
+            }
+        }
+    }
+
+    /// Describe the game to play from the command line and the configuration
+    fn game(&self, config: &Config) -> SavedGame {
+        let game = SavedGame {
+            moves: self.transcript.clone().unwrap_or_default(),
+            position: self.position.clone(),
+            black: config.black.clone(),
+            white: config.white.clone(),
+            board: config.board,
+            ..SavedGame::default()
+        };
+        self.start.unwrap_or_default().apply(game, &mut self.rng())
+    }
+
+    /// Return the generator of the random starts
+    fn rng(&self) -> fastrand::Rng {
+        self.seed.map_or_else(fastrand::Rng::new, fastrand::Rng::with_seed)
+    }
+
+    /// Verify if a game to play is given on the command line
+    fn has_game(&self) -> bool {
+        self.load_path().is_some()
+            || self.position.is_some()
+            || self.transcript.is_some()
+            || self.black.is_some()
+            || self.white.is_some()
+            || self.time_limit.is_some()
+            || self.start.is_some()
+    }
+
+    /// Return the file of the game to resume or to replay
+    fn load_path(&self) -> Option<PathBuf> {
+        if self.resume {
+            SavedGame::autosave_path()
+        } else {
+            self.load.clone().or_else(|| self.replay.clone())
+        }
+    }
+}
+
 fn main() -> GameResult {
     // Init the logger
     env_logger::init();
 
+    let args = Args::parse();
+
+    // Load the configuration, an invalid configuration is reported then ignored
+    let mut config = Config::load(args.config.as_deref()).unwrap_or_else(|err| {
+        error!("{}", err);
+        eprintln!("{}\nThe default configuration is used instead.", err);
+        Config::default()
+    });
+    if let Some((width, height)) = args.board_size {
+        config.board = BoardConfig { width, height };
+    }
+    if args.mute {
+        config.sound.mute = true;
+    }
+
+    if let Some(Err(err)) = args.start.map(|start| start.check(config.board_size())) {
+        exit_with_error(err);
+    }
+
+    // The game come from a saved game or from the command line
+    let game = match args.load_path() {
+        Some(path) => SavedGame::load(&path).unwrap_or_else(|err| exit_with_error(err)),
+        None => args.game(&config),
+    };
+    let (mut player_black, mut player_white) = game.players();
+    args.setup_player(&mut player_black, args.black);
+    args.setup_player(&mut player_white, args.white);
+
+    if let Some(path) = &args.analysis {
+        let depth = args.analysis_depth.unwrap_or(DEFAULT_ANALYSIS_DEPTH);
+        let analysis = Analysis::new(&game, Engine::new(depth)).unwrap_or_else(|err| exit_with_error(err));
+        return analysis.save(path).map_err(ggez::GameError::from);
+    }
+
+    if let Some(starts) = args.arena {
+        let start = args.start.unwrap_or_default();
+        let depths = match args.arena_depths[..] {
+            [first, second] => (first, second),
+            [] => (DEFAULT_AI_LEVEL, DEFAULT_AI_LEVEL),
+            _ => exit_with_error("--arena-depths needs the depths of two engines, e.g. \"4,2\""),
+        };
+        let mut arena = Arena::new(Engine::new(depths.0), Engine::new(depths.1))
+            .set_start(start)
+            .set_board_size(config.board_size());
+        if let Some(seed) = args.seed {
+            arena = arena.set_seed(seed);
+        }
+        println!("Depth {} against depth {}, start {}", depths.0, depths.1, start);
+        return arena.run(starts, io::stdout()).map(|_| ()).map_err(ggez::GameError::from);
+    }
+
+    if let Some(path) = &args.generate_puzzles {
+        let games = fs::read_to_string(path).unwrap_or_else(|err| exit_with_error(format!("cannot read {}: {}", path.display(), err)));
+        let max_empty_cases = args.puzzle_empty.map_or(DEFAULT_PUZZLE_EMPTY_CASES, usize::from);
+        for (index, transcript) in games.lines().enumerate().filter(|(_, line)| !line.trim().is_empty()) {
+            let game = SavedGame { moves: transcript.trim().to_string(), board: config.board, ..SavedGame::default() };
+            let puzzles = Puzzle::generate(&game, max_empty_cases)
+                .unwrap_or_else(|err| exit_with_error(format!("invalid game at line {}: {}", index + 1, err)));
+            for puzzle in puzzles {
+                println!("{}", puzzle.to_position_string());
+            }
+        }
+        return Ok(());
+    }
+
+    if args.headless {
+        let (board, turn) = game.replay().unwrap_or_else(|err| exit_with_error(err));
+        let stdin = io::stdin();
+        return headless::run(board, turn, &player_black, &player_white, stdin.lock(), io::stdout())
+            .map_err(ggez::GameError::from);
+    }
+
+    let mut theme = match (&args.theme, &game.theme) {
+        (Some(name), _) => match Theme::by_name(name) {
+            Some(theme) => theme,
+            None => ThemeConfig::load(Path::new(name)).unwrap_or_else(|err| exit_with_error(err)).theme(),
+        },
+        (None, Some(theme)) => theme.theme(),
+        (None, None) => config.theme(),
+    };
+    if args.no_animation {
+        theme.animation_duration = None;
+    }
+    if args.large_markers {
+        theme.large_markers = true;
+    }
+
+    if let Some(path) = &args.export_svg {
+        let (board, _) = game.replay().unwrap_or_else(|err| exit_with_error(err));
+        let moves = if args.move_numbers { game.moves().unwrap_or_default() } else { Vec::new() };
+        let diagram = Diagram::new(&board)
+            .set_theme(theme)
+            .set_moves(&moves)
+            .set_highlights(&args.highlight);
+        return fs::write(path, diagram.to_svg()).map_err(ggez::GameError::from);
+    }
+    if let Some(path) = &args.export_gif {
+        let gif = GameGif::new(&game)
+            .unwrap_or_else(|err| exit_with_error(err))
+            .set_theme(theme)
+            .set_delay(args.gif_delay.unwrap_or(DEFAULT_GIF_DELAY));
+        return gif.save(path).map_err(ggez::GameError::from);
+    }
+
     // Here we use a ContextBuilder to setup metadata about our game. First the title and author
-    let (ctx, event_loop)  = ggez::ContextBuilder::new(
+    let (mut ctx, event_loop)  = ggez::ContextBuilder::new(
         env!("CARGO_PKG_NAME"),
         env!("CARGO_PKG_AUTHORS"))
         .add_resource_path::<PathBuf>(
@@ -37,19 +402,56 @@ fn main() -> GameResult {
             .title("Othello")
             .icon("/images/icon_window.png")
         )
-        // Now we get to set the size of the window,
-        // which we use our SCREEN_SIZE constant from earlier to help with
+        // Now we get to set the size of the window from the configuration, the window can be resized
         .window_mode(ggez::conf::WindowMode::default()
-            .dimensions(SCREEN_SIZE.0 as f32, SCREEN_SIZE.1 as f32),
+            .dimensions(config.window_size().0, config.window_size().1)
+            .resizable(true)
+            .min_dimensions(MIN_SCREEN_SIZE.0 as f32, MIN_SCREEN_SIZE.1 as f32),
         )
         // And finally we attempt to build the context and create the window.
         // If it fails, we panic with the message "Failed to build ggez context"
         .build()
         .expect("Failed to build ggez context");
+    App::fit_screen(&mut ctx)?;
+
+    let app = App::new(config, theme.clone());
+    if args.replay.is_some() {
+        let mut replay = Replay::new(&game)
+            .unwrap_or_else(|err| exit_with_error(err))
+            .set_theme(theme);
+        if let Some(speed) = args.replay_speed {
+            replay = replay.autoplay(speed);
+        }
+        event::run(ctx, event_loop, app.with_replay(replay))
+    }
+
+    if let Some(path) = &args.puzzles {
+        let puzzles = Puzzle::load(path).unwrap_or_else(|err| exit_with_error(err));
+        event::run(ctx, event_loop, app.with_scene(Box::new(PuzzleScene::new(puzzles, theme))))
+    }
+
+    if args.editor {
+        let (board, turn) = game.replay().unwrap_or_else(|err| exit_with_error(err));
+        event::run(ctx, event_loop, app.with_scene(Box::new(Editor::new(board, turn, theme))))
+    }
+
+    // Without a game on the command line, the main menu is shown
+    if !args.has_game() {
+        event::run(ctx, event_loop, app)
+    }
 
     // Next we create a new instance of our Game struct, which implements EventHandler
-    let state = Othello::new(PLAYER_ONE, PLAYER_TWO);
+    let mut state = Othello::new(player_black, player_white)
+        .set_theme(theme)
+        .resume(&game)
+        .unwrap_or_else(|err| exit_with_error(err));
+    if let Some(path) = args.load {
+        state = state.set_save_path(path);
+    }
+    if let Some(time_limit) = args.time_limit {
+        state = state.set_time_limit(time_limit);
+    }
 
     // And finally we actually run our game, passing in our context, event_loop and state.
-    event::run(ctx, event_loop, state)
+    event::run(ctx, event_loop, app.with_game(state))
 }
@@ -0,0 +1,726 @@
+//! Menus of the window: the main menu, the new game setup and the settings
+
+use std::fs;
+
+use ggez::{Context, GameResult};
+use ggez::event::{KeyCode, KeyMods, MouseButton};
+use ggez::graphics::{self, Color, Rect};
+use log::info;
+
+use crate::*;
+
+/// Entries of the main menu
+pub const MAIN_MENU: [&str; 8] = ["New game", "Load game", "Replay", "Tutorial", "Puzzles", "Board editor", "Settings", "Quit"];
+
+/// Openings that can be played before a game on a 8x8 board (name, transcript)
+pub const OPENINGS: [(&str, &str); 4] = [
+    ("Standard", ""),
+    ("Tiger", "f5d6c3d3c4"),
+    ("Buffalo", "f5f6e6f4c3"),
+    ("Heath", "f5f6e6f4g5"),
+];
+
+/// Square sizes of board proposed in the new game setup
+const BOARD_SIZES: [i16; 7] = [4, 6, 8, 10, 12, 14, 16];
+
+/// A list of entries chosen with the keyboard or the mouse
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Menu {
+    title: String,
+    entries: Vec<String>,
+    selected: usize,
+    /// A message shown under the entries (e.g. an error)
+    message: Option<String>,
+}
+
+/// What the user did in a menu, with the index of the entry
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MenuEvent {
+    /// The entry is chosen (Enter, Space or left click)
+    Select(usize),
+    /// The value of the entry is decreased (Left or right click)
+    Previous(usize),
+    /// The value of the entry is increased (Right)
+    Next(usize),
+    /// Leave the menu (Escape)
+    Back,
+}
+
+impl Menu {
+    /// Create a menu, the first entry is selected
+    pub fn new<S: Into<String>>(title: S, entries: Vec<String>) -> Self {
+        Menu { title: title.into(), entries, selected: 0, message: None }
+    }
+
+    /// Return the index of the selected entry
+    pub fn selected(&self) -> usize {
+        self.selected
+    }
+
+    /// Return the entries of the menu
+    pub fn entries(&self) -> &[String] {
+        &self.entries
+    }
+
+    /// Change the entries, the same index stay selected
+    pub fn set_entries(&mut self, entries: Vec<String>) {
+        self.selected = self.selected.min(entries.len().saturating_sub(1));
+        self.entries = entries;
+    }
+
+    /// Show a message under the entries, or hide it
+    pub fn set_message(&mut self, message: Option<String>) {
+        self.message = message;
+    }
+
+    /// Handle a key of the keyboard
+    pub fn key_down(&mut self, keycode: KeyCode) -> Option<MenuEvent> {
+        match keycode {
+            KeyCode::Up | KeyCode::W => {
+                self.selected = (self.selected + self.entries.len() - 1) % self.entries.len();
+                None
+            }
+            KeyCode::Down | KeyCode::S => {
+                self.selected = (self.selected + 1) % self.entries.len();
+                None
+            }
+            KeyCode::Left | KeyCode::A => Some(MenuEvent::Previous(self.selected)),
+            KeyCode::Right | KeyCode::D => Some(MenuEvent::Next(self.selected)),
+            KeyCode::Return | KeyCode::NumpadEnter | KeyCode::Space => Some(MenuEvent::Select(self.selected)),
+            KeyCode::Escape => Some(MenuEvent::Back),
+            _ => None,
+        }
+    }
+
+    /// Handle a click on the screen of area `screen`
+    pub fn click(&mut self, screen: Rect, button: MouseButton, x: f32, y: f32, font_scale: f32) -> Option<MenuEvent> {
+        let index = (0..self.entries.len())
+            .find(|index| self.entry_rect(screen, *index, font_scale).contains(glam::Vec2::new(x, y)))?;
+        self.selected = index;
+        match button {
+            MouseButton::Left => Some(MenuEvent::Select(index)),
+            MouseButton::Right => Some(MenuEvent::Previous(index)),
+            _ => None,
+        }
+    }
+
+    /// Compute the area of an entry when the menu is drawn on `screen`
+    pub fn entry_rect(&self, screen: Rect, index: usize, font_scale: f32) -> Rect {
+        let line_height = 2.5 * font_scale;
+        let top = screen.y + screen.h / 3.0;
+        Rect::new(screen.x + screen.w / 4.0, top + index as f32 * line_height, screen.w / 2.0, line_height)
+    }
+
+    /// Draw the menu in the middle of the screen
+    ///
+    /// The texts are scaled like the board with the size of the window.
+    pub fn draw(&self, ctx: &mut Context, theme: &Theme) -> GameResult {
+        let screen = graphics::screen_coordinates(ctx);
+        let font_scale = Layout::new((screen.w, screen.h)).font_size(theme.font_scale);
+        let font = graphics::Font::new(ctx, theme.font_path.as_ref())?;
+
+        let title = graphics::Text::new((self.title.as_str(), font, 3.0 * font_scale));
+        let x = screen.x + (screen.w - title.dimensions(ctx).w) / 2.0;
+        graphics::draw(ctx, &title, (glam::Vec2::new(x, screen.y + screen.h / 6.0), theme.grid_color))?;
+
+        for (index, entry) in self.entries.iter().enumerate() {
+            let area = self.entry_rect(screen, index, font_scale);
+            if index == self.selected {
+                let highlight = graphics::MeshBuilder::new()
+                    .rectangle(graphics::DrawMode::fill(), area, Color::new(0.0, 0.0, 0.0, 0.6))?
+                    .build(ctx)?;
+                graphics::draw(ctx, &highlight, graphics::DrawParam::default())?;
+            }
+            let text = graphics::Text::new((entry.as_str(), font, font_scale));
+            let dimensions = text.dimensions(ctx);
+            let point = glam::Vec2::new(area.x + (area.w - dimensions.w) / 2.0, area.y + (area.h - dimensions.h) / 2.0);
+            let color = if index == self.selected { Color::WHITE } else { theme.grid_color };
+            graphics::draw(ctx, &text, (point, color))?;
+        }
+
+        if let Some(message) = &self.message {
+            let area = self.entry_rect(screen, self.entries.len() + 1, font_scale);
+            let text = graphics::Text::new((message.as_str(), font, font_scale));
+            let x = screen.x + (screen.w - text.dimensions(ctx).w) / 2.0;
+            graphics::draw(ctx, &text, (glam::Vec2::new(x, area.y), theme.grid_color))?;
+        }
+        Ok(())
+    }
+}
+
+/// The scene of the main menu
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MainMenu {
+    menu: Menu,
+}
+
+/// The scene of the new game setup
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SetupMenu {
+    menu: Menu,
+}
+
+/// The scene of the settings
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SettingsMenu {
+    menu: Menu,
+}
+
+impl MainMenu {
+    /// Create the main menu
+    pub fn new() -> Self {
+        MainMenu { menu: Menu::new("Othello", MAIN_MENU.iter().map(|entry| entry.to_string()).collect()) }
+    }
+
+    /// Load the last saved game: the one saved with the keyboard or the autosave
+    fn last_saved_game() -> Result<SavedGame, String> {
+        let newest = [SavedGame::default_path(), SavedGame::autosave_path()]
+            .into_iter()
+            .flatten()
+            .filter_map(|path| fs::metadata(&path).and_then(|data| data.modified()).ok().map(|time| (time, path)))
+            .max();
+        match newest {
+            Some((_, path)) => SavedGame::load(&path).map_err(|err| err.to_string()),
+            None => Err(String::from("There is no saved game")),
+        }
+    }
+
+    /// Handle what the user did in the menu
+    fn event(&mut self, world: &mut World, menu_event: MenuEvent) -> Transition {
+        let index = match menu_event {
+            MenuEvent::Select(index) => index,
+            MenuEvent::Back => {
+                info!("EXIT from key Escape");
+                return Transition::Quit;
+            }
+            _ => return Transition::None,
+        };
+        let result = match MAIN_MENU[index] {
+            "New game" => Ok(Transition::Push(Box::new(SetupMenu::new(world)))),
+            "Load game" => MainMenu::last_saved_game()
+                .and_then(|game| start_game(world, &game))
+                .map(|game| Transition::Push(Box::new(game))),
+            "Replay" => MainMenu::last_saved_game()
+                .and_then(|game| Replay::new(&game).map_err(|err| err.to_string()))
+                .map(|replay| Transition::Push(Box::new(replay.set_theme(world.theme.clone())))),
+            "Tutorial" => Ok(Transition::Push(Box::new(Tutorial::new(world.theme.clone())))),
+            "Puzzles" => Ok(Transition::Push(Box::new(PuzzleScene::new(Puzzle::builtin(), world.theme.clone())))),
+            "Board editor" => {
+                let board = Board::with_size(world.setup.board_size);
+                Ok(Transition::Push(Box::new(Editor::new(board, Piece::BLACK, world.theme.clone()))))
+            }
+            "Settings" => Ok(Transition::Push(Box::new(SettingsMenu::new(world)))),
+            _ => {
+                info!("EXIT from the main menu");
+                Ok(Transition::Quit)
+            }
+        };
+        result.unwrap_or_else(|err| {
+            self.menu.set_message(Some(err));
+            Transition::None
+        })
+    }
+}
+
+impl Default for MainMenu {
+    fn default() -> Self {
+        MainMenu::new()
+    }
+}
+
+impl SetupMenu {
+    /// Create the new game setup with the last choices
+    pub fn new(world: &World) -> Self {
+        SetupMenu { menu: Menu::new("New game", world.setup.entries()) }
+    }
+
+    /// Handle what the user did in the menu
+    fn event(&mut self, world: &mut World, menu_event: MenuEvent) -> Transition {
+        let (index, forward) = match menu_event {
+            MenuEvent::Select(index) | MenuEvent::Next(index) => (index, true),
+            MenuEvent::Previous(index) => (index, false),
+            MenuEvent::Back => return Transition::Pop,
+        };
+        match (SetupEntry::ALL[index], menu_event) {
+            (SetupEntry::Start, MenuEvent::Select(_)) => {
+                match start_game(world, &world.setup.game(&world.config)) {
+                    Ok(game) => return Transition::Replace(Box::new(game)),
+                    Err(err) => self.menu.set_message(Some(err)),
+                }
+            }
+            (SetupEntry::Back, MenuEvent::Select(_)) => return Transition::Pop,
+            (entry, _) => {
+                world.setup.change(entry, forward);
+                self.menu.set_entries(world.setup.entries());
+            }
+        }
+        Transition::None
+    }
+}
+
+impl SettingsMenu {
+    /// Create the settings menu
+    pub fn new(world: &World) -> Self {
+        SettingsMenu { menu: Menu::new("Settings", world.settings.entries()) }
+    }
+
+    /// Handle what the user did in the menu
+    fn event(&mut self, world: &mut World, menu_event: MenuEvent) -> Transition {
+        let (index, forward) = match menu_event {
+            MenuEvent::Select(index) | MenuEvent::Next(index) => (index, true),
+            MenuEvent::Previous(index) => (index, false),
+            MenuEvent::Back => return Transition::Pop,
+        };
+        match (SettingsEntry::ALL[index], menu_event) {
+            (SettingsEntry::Back, MenuEvent::Select(_)) => Transition::Pop,
+            (entry, _) => {
+                world.settings.change(entry, forward);
+                world.theme = world.settings.theme();
+                self.menu.set_entries(world.settings.entries());
+                Transition::None
+            }
+        }
+    }
+}
+
+/// Create the game of a saved game with the theme of the menus
+pub(crate) fn start_game(world: &World, game: &SavedGame) -> Result<Othello, String> {
+    let (player_black, player_white) = game.players();
+    Othello::new(player_black, player_white)
+        .set_theme(world.theme.clone())
+        .set_evaluation(world.settings.evaluation)
+        .resume(game)
+        .map_err(|err| err.to_string())
+}
+
+/// Implement [`Scene`] for a scene that only show a menu
+macro_rules! menu_scene {
+    ($scene:ty) => {
+        impl Scene for $scene {
+            fn draw(&mut self, ctx: &mut Context, world: &World) -> GameResult {
+                graphics::clear(ctx, world.theme.background_color);
+                self.menu.draw(ctx, &world.theme)
+            }
+
+            fn key_down(&mut self, _ctx: &mut Context, world: &mut World, keycode: KeyCode, _keymod: KeyMods) -> Transition {
+                match self.menu.key_down(keycode) {
+                    Some(menu_event) => self.event(world, menu_event),
+                    None => Transition::None,
+                }
+            }
+
+            fn mouse_down(&mut self, ctx: &mut Context, world: &mut World, button: MouseButton, x: f32, y: f32) -> Transition {
+                let screen = graphics::screen_coordinates(ctx);
+                let font_scale = Layout::new((screen.w, screen.h)).font_size(world.theme.font_scale);
+                match self.menu.click(screen, button, x, y, font_scale) {
+                    Some(menu_event) => self.event(world, menu_event),
+                    None => Transition::None,
+                }
+            }
+        }
+    };
+}
+
+menu_scene!(MainMenu);
+menu_scene!(SetupMenu);
+menu_scene!(SettingsMenu);
+
+/// Choices of the new game setup
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct GameSetup {
+    /// The black player is a computer
+    pub black_computer: bool,
+    /// The white player is a computer
+    pub white_computer: bool,
+    /// Level of the computer players
+    pub level: u8,
+    /// Size of the board (columns, rows)
+    pub board_size: (i16, i16),
+    /// Index in [`OPENINGS`], only the standard start is possible if the board is not 8x8
+    pub opening: usize,
+    /// The named openings are only played from the standard start
+    pub start: StartPosition,
+}
+
+/// Entries of the new game setup
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SetupEntry {
+    Black,
+    White,
+    Level,
+    BoardSize,
+    Opening,
+    Position,
+    Start,
+    Back,
+}
+
+impl SetupEntry {
+    /// Every entry in the order of the menu
+    pub const ALL: [SetupEntry; 8] = [
+        SetupEntry::Black,
+        SetupEntry::White,
+        SetupEntry::Level,
+        SetupEntry::BoardSize,
+        SetupEntry::Opening,
+        SetupEntry::Position,
+        SetupEntry::Start,
+        SetupEntry::Back,
+    ];
+}
+
+impl GameSetup {
+    /// Create the setup of the configured game
+    pub fn new(config: &Config) -> Self {
+        GameSetup {
+            black_computer: config.black.kind == PlayerType::Computer,
+            white_computer: config.white.kind == PlayerType::Computer,
+            level: config.white.level.clamp(1, MAX_AI_LEVEL),
+            board_size: config.board_size(),
+            opening: 0,
+            start: StartPosition::Standard,
+        }
+    }
+
+    /// Write the entries of the menu
+    pub fn entries(&self) -> Vec<String> {
+        let controller = |computer: bool| if computer { "Computer" } else { "Human" };
+        SetupEntry::ALL.iter().map(|entry| match entry {
+            SetupEntry::Black => format!("Black: {}", controller(self.black_computer)),
+            SetupEntry::White => format!("White: {}", controller(self.white_computer)),
+            SetupEntry::Level => format!("Computer level: {}", self.level),
+            SetupEntry::BoardSize => format!("Board size: {}x{}", self.board_size.0, self.board_size.1),
+            SetupEntry::Opening => format!("Opening: {}", OPENINGS[self.opening].0),
+            SetupEntry::Position => format!("Start position: {}", self.start),
+            SetupEntry::Start => String::from("Start"),
+            SetupEntry::Back => String::from("Back"),
+        }).collect()
+    }
+
+    /// Change the value of an entry, `forward` choose the next value else the previous one
+    pub fn change(&mut self, entry: SetupEntry, forward: bool) {
+        match entry {
+            SetupEntry::Black => self.black_computer = !self.black_computer,
+            SetupEntry::White => self.white_computer = !self.white_computer,
+            SetupEntry::Level => self.level = cycle(self.level as usize - 1, MAX_AI_LEVEL as usize, forward) as u8 + 1,
+            SetupEntry::BoardSize => {
+                let index = BOARD_SIZES.iter().position(|size| (*size, *size) == self.board_size);
+                let index = match index {
+                    Some(index) => cycle(index, BOARD_SIZES.len(), forward),
+                    // A size of the configuration that is not proposed
+                    None => BOARD_SIZES.iter().position(|size| *size == DEFAULT_GRID_SIZE.0).unwrap_or(0),
+                };
+                self.board_size = (BOARD_SIZES[index], BOARD_SIZES[index]);
+                if self.board_size != (8, 8) {
+                    self.opening = 0;
+                }
+                if !self.start.is_available(self.board_size) {
+                    self.start = StartPosition::Standard;
+                }
+            }
+            SetupEntry::Opening if self.board_size == (8, 8) && self.start == StartPosition::Standard => {
+                self.opening = cycle(self.opening, OPENINGS.len(), forward);
+            }
+            SetupEntry::Position => {
+                let starts: Vec<StartPosition> = StartPosition::ALL.into_iter()
+                    .filter(|start| start.is_available(self.board_size))
+                    .collect();
+                let index = starts.iter().position(|start| *start == self.start).unwrap_or(0);
+                self.start = starts[cycle(index, starts.len(), forward)];
+                if self.start != StartPosition::Standard {
+                    self.opening = 0;
+                }
+            }
+            _ => {}
+        }
+    }
+
+    /// Describe the game to start, the names of the players come from the configuration
+    ///
+    /// The opening of a random or balanced start is chosen each time.
+    pub fn game(&self, config: &Config) -> SavedGame {
+        let player = |config: &PlayerConfig, computer: bool| PlayerConfig {
+            name: config.name.clone(),
+            kind: if computer { PlayerType::Computer } else { PlayerType::Human },
+            level: self.level,
+            time: None,
+        };
+        let game = SavedGame {
+            moves: String::from(OPENINGS[self.opening].1),
+            black: player(&config.black, self.black_computer),
+            white: player(&config.white, self.white_computer),
+            board: BoardConfig { width: self.board_size.0, height: self.board_size.1 },
+            ..SavedGame::default()
+        };
+        self.start.apply(game, &mut fastrand::Rng::new())
+    }
+}
+
+/// Choices of the settings menu
+#[derive(Debug, Clone)]
+pub struct Settings {
+    /// The configured theme, used when `theme_name` is `None`
+    configured_theme: Theme,
+    /// Index in [`THEME_NAMES`]
+    theme_name: Option<usize>,
+    /// Animate the moves
+    pub animation: bool,
+    /// Show the valid moves
+    pub valid_moves: bool,
+    /// Show the coordinates around the board
+    pub coordinates: bool,
+    /// Draw bigger marks on the board
+    pub large_markers: bool,
+    /// Play the sound effects
+    pub sound: bool,
+    /// Volume of the sound effects (in percent)
+    pub volume: u8,
+    /// Show the evaluation of the engine beside the board during a game
+    pub evaluation: bool,
+}
+
+/// Entries of the settings menu
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SettingsEntry {
+    Theme,
+    Animation,
+    ValidMoves,
+    Coordinates,
+    LargeMarkers,
+    Sound,
+    Volume,
+    Evaluation,
+    Back,
+}
+
+impl SettingsEntry {
+    /// Every entry in the order of the menu
+    pub const ALL: [SettingsEntry; 9] = [
+        SettingsEntry::Theme,
+        SettingsEntry::Animation,
+        SettingsEntry::ValidMoves,
+        SettingsEntry::Coordinates,
+        SettingsEntry::LargeMarkers,
+        SettingsEntry::Sound,
+        SettingsEntry::Volume,
+        SettingsEntry::Evaluation,
+        SettingsEntry::Back,
+    ];
+}
+
+impl Settings {
+    /// Create the settings of the theme and of the sound in use
+    pub fn new(theme: &Theme, sound: &SoundConfig) -> Self {
+        Settings {
+            configured_theme: theme.clone(),
+            theme_name: None,
+            animation: theme.animation_duration.is_some(),
+            valid_moves: theme.valid_moves_color.is_some(),
+            coordinates: theme.coordinates_color.is_some(),
+            large_markers: theme.large_markers,
+            sound: !sound.mute,
+            // The volume is changed by steps of 10%
+            volume: (sound.volume * 10.0).round() as u8 * 10,
+            evaluation: true,
+        }
+    }
+
+    /// Write the entries of the menu
+    pub fn entries(&self) -> Vec<String> {
+        let on_off = |on: bool| if on { "on" } else { "off" };
+        SettingsEntry::ALL.iter().map(|entry| match entry {
+            SettingsEntry::Theme => format!("Theme: {}", self.theme_name()),
+            SettingsEntry::Animation => format!("Animations: {}", on_off(self.animation)),
+            SettingsEntry::ValidMoves => format!("Valid moves: {}", on_off(self.valid_moves)),
+            SettingsEntry::Coordinates => format!("Coordinates: {}", on_off(self.coordinates)),
+            SettingsEntry::LargeMarkers => format!("Large markers: {}", on_off(self.large_markers)),
+            SettingsEntry::Sound => format!("Sound: {}", on_off(self.sound)),
+            SettingsEntry::Volume => format!("Volume: {}%", self.volume),
+            SettingsEntry::Evaluation => format!("Evaluation: {}", on_off(self.evaluation)),
+            SettingsEntry::Back => String::from("Back"),
+        }).collect()
+    }
+
+    /// Return the name of the chosen theme, `configured` for the theme of the configuration
+    pub fn theme_name(&self) -> &'static str {
+        self.theme_name.map_or("configured", |index| THEME_NAMES[index])
+    }
+
+    /// Change the value of an entry, `forward` choose the next value else the previous one
+    pub fn change(&mut self, entry: SettingsEntry, forward: bool) {
+        match entry {
+            SettingsEntry::Theme => {
+                // The configured theme is before the first built-in theme
+                let index = self.theme_name.map_or(0, |index| index + 1);
+                let index = cycle(index, THEME_NAMES.len() + 1, forward);
+                self.theme_name = index.checked_sub(1);
+            }
+            SettingsEntry::Animation => self.animation = !self.animation,
+            SettingsEntry::ValidMoves => self.valid_moves = !self.valid_moves,
+            SettingsEntry::Coordinates => self.coordinates = !self.coordinates,
+            SettingsEntry::LargeMarkers => self.large_markers = !self.large_markers,
+            SettingsEntry::Sound => self.sound = !self.sound,
+            SettingsEntry::Volume => self.volume = cycle(self.volume as usize / 10, 11, forward) as u8 * 10,
+            SettingsEntry::Evaluation => self.evaluation = !self.evaluation,
+            SettingsEntry::Back => {}
+        }
+    }
+
+    /// Return the volume of the sound effects (from 0 to 1), 0 if the sound is off
+    pub fn volume(&self) -> f32 {
+        if self.sound {
+            self.volume as f32 / 100.0
+        } else {
+            0.0
+        }
+    }
+
+    /// Create the chosen theme
+    pub fn theme(&self) -> Theme {
+        let mut theme = self.theme_name
+            .and_then(|index| Theme::by_name(THEME_NAMES[index]))
+            .unwrap_or_else(|| self.configured_theme.clone());
+        if !self.animation {
+            theme.animation_duration = None;
+        } else if theme.animation_duration.is_none() {
+            theme.animation_duration = DEFAULT_THEME.animation_duration;
+        }
+        if !self.valid_moves {
+            theme.valid_moves_color = None;
+        } else if theme.valid_moves_color.is_none() {
+            theme.valid_moves_color = DEFAULT_THEME.valid_moves_color;
+        }
+        if !self.coordinates {
+            theme.coordinates_color = None;
+        } else if theme.coordinates_color.is_none() {
+            theme.coordinates_color = Some(theme.grid_color);
+        }
+        theme.large_markers = self.large_markers;
+        theme
+    }
+}
+
+/// Return the next (or previous) index in a list of `len` values
+fn cycle(index: usize, len: usize, forward: bool) -> usize {
+    if forward {
+        (index + 1) % len
+    } else {
+        (index + len - 1) % len
+    }
+}
+
+#[allow(unreachable_code)]
+#[cfg(test)]
+mod tests {
+    use ggez::event::{KeyCode, MouseButton};
+    use ggez::graphics::Rect;
+
+    use crate::*;
+
+    #[test]
+    fn navigate() {
+        let mut menu = Menu::new("Othello", MAIN_MENU.iter().map(|entry| entry.to_string()).collect());
+        assert_eq!(menu.key_down(KeyCode::Up), None);
+        assert_eq!(menu.selected(), 7);
+        menu.key_down(KeyCode::Down);
+        menu.key_down(KeyCode::Down);
+        assert_eq!(menu.key_down(KeyCode::Return), Some(MenuEvent::Select(1)));
+        assert_eq!(menu.key_down(KeyCode::Left), Some(MenuEvent::Previous(1)));
+        assert_eq!(menu.key_down(KeyCode::Escape), Some(MenuEvent::Back));
+    }
+
+    #[test]
+    fn click() {
+        let mut menu = Menu::new("Othello", MAIN_MENU.iter().map(|entry| entry.to_string()).collect());
+        let screen = Rect::new(0.0, 0.0, 800.0, 600.0);
+        let area = menu.entry_rect(screen, 3, 15.0);
+        let event = menu.click(screen, MouseButton::Left, area.x + 1.0, area.y + 1.0, 15.0);
+        assert_eq!(event, Some(MenuEvent::Select(3)));
+        assert_eq!(menu.selected(), 3);
+        assert_eq!(menu.click(screen, MouseButton::Left, 0.0, 0.0, 15.0), None);
+    }
+
+    #[test]
+    fn openings() {
+        for (name, transcript) in OPENINGS {
+            let moves = parse_transcript(transcript).unwrap();
+            assert!(play_transcript(&mut Board::default(), Piece::BLACK, &moves).is_ok(), "{}", name);
+        }
+    }
+
+    #[test]
+    fn setup() {
+        let config = Config::default();
+        let mut setup = GameSetup::new(&config);
+        setup.change(SetupEntry::White, true);
+        setup.change(SetupEntry::Level, false);
+        setup.change(SetupEntry::Opening, true);
+        assert_eq!(setup.entries()[..5], [
+            "Black: Human", "White: Computer", "Computer level: 2", "Board size: 8x8", "Opening: Tiger",
+        ]);
+        let game = setup.game(&config);
+        assert_eq!(game.moves, "f5d6c3d3c4");
+        assert_eq!(game.players().1, Player::computer("Marie Curie", Piece::WHITE, 2));
+
+        // The openings are only played on a 8x8 board
+        setup.change(SetupEntry::BoardSize, true);
+        assert_eq!(setup.board_size, (10, 10));
+        assert_eq!(setup.opening, 0);
+        setup.change(SetupEntry::Opening, true);
+        assert_eq!(setup.opening, 0);
+
+        // The balanced openings are only proposed on a 8x8 board
+        setup.change(SetupEntry::Position, true);
+        assert_eq!(setup.start, StartPosition::Diagonal);
+        setup.change(SetupEntry::Position, true);
+        assert_eq!(setup.start, StartPosition::Random(DEFAULT_RANDOM_MOVES));
+        assert_eq!(setup.game(&config).moves().unwrap().len(), DEFAULT_RANDOM_MOVES as usize);
+        setup.change(SetupEntry::BoardSize, false);
+        setup.change(SetupEntry::Position, false);
+        assert_eq!(setup.entries()[5], "Start position: Balanced opening");
+        assert_eq!(setup.game(&config).moves().unwrap().len(), 8);
+        setup.change(SetupEntry::Opening, true);
+        assert_eq!(setup.opening, 0);
+        setup.change(SetupEntry::BoardSize, true);
+        assert_eq!(setup.start, StartPosition::Standard);
+    }
+
+    #[test]
+    fn settings() {
+        let mut settings = Settings::new(&DEFAULT_THEME, &SoundConfig::default());
+        assert_eq!(settings.entries()[0], "Theme: configured");
+        settings.change(SettingsEntry::Theme, true);
+        assert_eq!(settings.entries()[0], "Theme: classic");
+        settings.change(SettingsEntry::Animation, true);
+        assert_eq!(settings.theme().animation_duration, None);
+        settings.change(SettingsEntry::Theme, true);
+        assert_eq!(settings.entries()[0], "Theme: dark");
+        assert_eq!(settings.theme().piece_style, PieceStyle::Outline);
+        settings.change(SettingsEntry::Theme, false);
+        settings.change(SettingsEntry::Theme, false);
+        assert_eq!(settings.entries()[0], "Theme: configured");
+        settings.change(SettingsEntry::Theme, false);
+        assert_eq!(settings.theme_name(), "color-blind");
+        settings.change(SettingsEntry::Theme, true);
+        settings.change(SettingsEntry::Coordinates, false);
+        assert_eq!(settings.entries()[3], "Coordinates: off");
+        assert_eq!(settings.theme().coordinates_color, None);
+        settings.change(SettingsEntry::LargeMarkers, true);
+        assert!(settings.theme().large_markers);
+
+        assert_eq!(settings.volume(), 0.8);
+        settings.change(SettingsEntry::Volume, true);
+        settings.change(SettingsEntry::Volume, true);
+        settings.change(SettingsEntry::Volume, true);
+        assert_eq!(settings.entries()[6], "Volume: 0%");
+        settings.change(SettingsEntry::Volume, false);
+        assert_eq!(settings.volume(), 1.0);
+        settings.change(SettingsEntry::Sound, true);
+        assert_eq!(settings.entries()[5], "Sound: off");
+        assert_eq!(settings.volume(), 0.0);
+
+        assert_eq!(settings.entries()[7], "Evaluation: on");
+        settings.change(SettingsEntry::Evaluation, true);
+        assert!(!settings.evaluation);
+    }
+}
@@ -0,0 +1,264 @@
+//! Text notations of the game
+//!
+//! - a case is written with its column letter then its row number: `a1` is the top left case
+//! - a transcript is the list of the played cases: `f5d6c3d3c4`
+//! - a position string write every case row by row (`X` black, `O` white, `-` empty)
+//!   followed by the side to move: `---------------------------OX------XO--------------------------- X`
+
+use std::error::Error;
+use std::fmt;
+
+use crate::*;
+
+/// Error raised while reading a text notation
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum NotationError {
+    /// The text is not a case like `f5`
+    InvalidCase(String),
+    /// The character is not a piece (`X`, `O` or `-`)
+    InvalidPiece(char),
+    /// The side to move is missing or is not `X` or `O`
+    InvalidTurn(String),
+    /// The number of cases cannot make a valid board
+    InvalidSize(usize),
+    /// The number of cases do not match the size of the board
+    InvalidLength { expected: usize, found: usize },
+    /// A move of a transcript cannot be played (the index start at 1)
+    IllegalMove { index: usize, case: String },
+}
+
+impl GridPosition {
+    /// Write the position as a case of the board (`a1` is the top left case)
+    pub fn to_notation(&self) -> String {
+        format!("{}{}", column_label(self.x), row_label(self.y))
+    }
+
+    /// Read a case of the board like `f5` (the case is not required to be in the board)
+    pub fn from_notation(case: &str) -> Result<Self, NotationError> {
+        let invalid = || NotationError::InvalidCase(case.to_string());
+        let mut chars = case.chars();
+        let column = chars.next().filter(char::is_ascii_alphabetic).ok_or_else(invalid)?;
+        let row: i16 = chars.as_str().parse().map_err(|_| invalid())?;
+        if row < 1 || !chars.as_str().starts_with(|c: char| c.is_ascii_digit()) {
+            return Err(invalid());
+        }
+        let x = (column.to_ascii_lowercase() as u8 - b'a') as i16;
+        Ok(GridPosition::new(x, row - 1))
+    }
+}
+
+/// Return the letter of a column of the board (`a` is the left column)
+pub fn column_label(x: i16) -> char {
+    (b'a' + x as u8) as char
+}
+
+/// Return the number of a row of the board (`1` is the top row)
+pub fn row_label(y: i16) -> String {
+    (y + 1).to_string()
+}
+
+/// Read a transcript like `f5d6c3` (spaces are ignored)
+pub fn parse_transcript(transcript: &str) -> Result<Vec<GridPosition>, NotationError> {
+    let transcript: String = transcript.chars().filter(|c| !c.is_whitespace()).collect();
+    let mut moves = Vec::new();
+    let mut rest = transcript.as_str();
+    while !rest.is_empty() {
+        if !rest.is_char_boundary(1) {
+            return Err(NotationError::InvalidCase(rest.to_string()));
+        }
+        // A case is a letter followed by the digits of the row
+        let end = rest[1..].find(|c: char| !c.is_ascii_digit()).map_or(rest.len(), |i| i + 1);
+        moves.push(GridPosition::from_notation(&rest[..end])?);
+        rest = &rest[end..];
+    }
+    Ok(moves)
+}
+
+/// Write a transcript like `f5d6c3`
+pub fn write_transcript(moves: &[GridPosition]) -> String {
+    moves.iter().map(GridPosition::to_notation).collect()
+}
+
+/// Play the moves of a transcript on the board from the side `turn`
+///
+/// Passes are not written in a transcript: when a player cannot play, the other one play.
+/// Return the side to move at the end of the transcript.
+pub fn play_transcript(board: &mut Board, mut turn: Piece, moves: &[GridPosition]) -> Result<Piece, NotationError> {
+    for (index, position) in moves.iter().enumerate() {
+        turn = play_transcript_move(board, turn, index, *position)?.next();
+    }
+    Ok(turn)
+}
+
+/// Play the move `index` (starting at 0) of a transcript when `turn` have to play
+///
+/// Return the side who played the move (the other one if `turn` have to pass).
+pub fn play_transcript_move(board: &mut Board, turn: Piece, index: usize, position: GridPosition) -> Result<Piece, NotationError> {
+    let turn = if board.can_play(turn) { turn } else { turn.next() };
+    board.play(position, turn)
+        .map_err(|_| NotationError::IllegalMove { index: index + 1, case: position.to_notation() })?;
+    Ok(turn)
+}
+
+impl Board {
+    /// Read a position string, return the board and the side to move
+    ///
+    /// If `size` is `None` the board must be a square.
+    pub fn from_position_string(position: &str, size: Option<(i16, i16)>) -> Result<(Board, Piece), NotationError> {
+        let position: String = position.chars().filter(|c| !c.is_whitespace()).collect();
+        let (cases, turn) = match position.char_indices().last() {
+            Some((index, turn)) => (&position[..index], turn),
+            None => return Err(NotationError::InvalidTurn(position)),
+        };
+        let turn = match turn {
+            'X' | 'x' => Piece::BLACK,
+            'O' | 'o' => Piece::WHITE,
+            _ => return Err(NotationError::InvalidTurn(turn.to_string())),
+        };
+
+        let count = cases.chars().count();
+        let size = match size {
+            Some(size) => size,
+            None => {
+                let side = (count as f64).sqrt() as i16;
+                (side, side)
+            }
+        };
+        if !Board::is_valid_size(size) {
+            return Err(NotationError::InvalidSize(count));
+        }
+        let expected = (size.0 * size.1) as usize;
+        if count != expected {
+            return Err(NotationError::InvalidLength { expected, found: count });
+        }
+
+        let mut board = Board::new(size);
+        for (index, case) in cases.chars().enumerate() {
+            let piece = match case {
+                'X' | 'x' | '*' => Piece::BLACK,
+                'O' | 'o' => Piece::WHITE,
+                '-' | '.' => Piece::EMPTY,
+                _ => return Err(NotationError::InvalidPiece(case)),
+            };
+            let index = index as i16;
+            board.set(GridPosition::new(index % size.0, index / size.0), piece);
+        }
+        Ok((board, turn))
+    }
+
+    /// Write the board and the side to move as a position string
+    pub fn to_position_string(&self, turn: Piece) -> String {
+        let (width, height) = self.size();
+        let mut position = String::with_capacity((width * height) as usize + 2);
+        for y in 0..height {
+            for x in 0..width {
+                position.push(piece_char(self.get(GridPosition::new(x, y))));
+            }
+        }
+        position.push(' ');
+        position.push(piece_char(turn));
+        position
+    }
+}
+
+/// Character of a piece in the position strings
+pub fn piece_char(piece: Piece) -> char {
+    match piece {
+        Piece::BLACK => 'X',
+        Piece::WHITE => 'O',
+        Piece::EMPTY => '-',
+    }
+}
+
+impl fmt::Display for NotationError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            NotationError::InvalidCase(case) => write!(f, "\"{}\" is not a case like \"f5\"", case),
+            NotationError::InvalidPiece(piece) => write!(f, "'{}' is not a piece ('X', 'O' or '-')", piece),
+            NotationError::InvalidTurn(turn) => write!(f, "\"{}\" is not a side to move ('X' or 'O')", turn),
+            NotationError::InvalidSize(count) => write!(f, "{} cases cannot make a valid board", count),
+            NotationError::InvalidLength { expected, found } => {
+                write!(f, "the position must have {} cases (found {})", expected, found)
+            }
+            NotationError::IllegalMove { index, case } => write!(f, "the move {} ({}) is not valid", index, case),
+        }
+    }
+}
+
+impl Error for NotationError {}
+
+#[allow(unreachable_code)]
+#[cfg(test)]
+mod tests {
+    use crate::*;
+
+    const START: &str = "---------------------------OX------XO--------------------------- X";
+
+    #[test]
+    fn case_notation() {
+        assert_eq!(GridPosition::new(0, 0).to_notation(), "a1");
+        assert_eq!(GridPosition::new(5, 4).to_notation(), "f5");
+        assert_eq!(GridPosition::new(9, 9).to_notation(), "j10");
+        assert_eq!(GridPosition::from_notation("f5"), Ok(GridPosition::new(5, 4)));
+        assert_eq!(GridPosition::from_notation("J10"), Ok(GridPosition::new(9, 9)));
+        assert!(GridPosition::from_notation("f0").is_err());
+        assert!(GridPosition::from_notation("5f").is_err());
+        assert!(GridPosition::from_notation("f+5").is_err());
+        assert!(GridPosition::from_notation("").is_err());
+    }
+
+    #[test]
+    fn transcript() {
+        let moves = parse_transcript("f5d6 c3j10").unwrap();
+        assert_eq!(moves, vec![
+            GridPosition::new(5, 4),
+            GridPosition::new(3, 5),
+            GridPosition::new(2, 2),
+            GridPosition::new(9, 9),
+        ]);
+        assert_eq!(write_transcript(&moves), "f5d6c3j10");
+        assert!(parse_transcript("f5d").is_err());
+    }
+
+    #[test]
+    fn play() {
+        let mut board = Board::default();
+        let moves = parse_transcript("f5d6c3").unwrap();
+        assert_eq!(play_transcript(&mut board, Piece::BLACK, &moves), Ok(Piece::WHITE));
+        assert_eq!(board.score(Piece::BLACK), 5);
+        assert_eq!(board.score(Piece::WHITE), 2);
+
+        let moves = parse_transcript("f5a1").unwrap();
+        assert_eq!(
+            play_transcript(&mut Board::default(), Piece::BLACK, &moves),
+            Err(NotationError::IllegalMove { index: 2, case: String::from("a1") })
+        );
+    }
+
+    #[test]
+    fn position_string() {
+        let (board, turn) = Board::from_position_string(START, None).unwrap();
+        assert_eq!(board, Board::default());
+        assert_eq!(turn, Piece::BLACK);
+        assert_eq!(board.to_position_string(turn), START);
+
+        let (board, turn) = Board::from_position_string("-----OX--XO----- O", None).unwrap();
+        assert_eq!(board, Board::with_size((4, 4)));
+        assert_eq!(turn, Piece::WHITE);
+
+        let (board, _) = Board::from_position_string("---------OX--XO--------- X", Some((4, 6))).unwrap();
+        assert_eq!(board, Board::with_size((4, 6)));
+    }
+
+    #[test]
+    fn invalid_position_string() {
+        assert_eq!(Board::from_position_string("-----OX--XO----- ?", None).unwrap_err(),
+                   NotationError::InvalidTurn(String::from("?")));
+        assert_eq!(Board::from_position_string("-----OX--XO----? X", None).unwrap_err(),
+                   NotationError::InvalidPiece('?'));
+        assert_eq!(Board::from_position_string("-----OX--XO---- X", None).unwrap_err(),
+                   NotationError::InvalidSize(15));
+        assert_eq!(Board::from_position_string("-----OX--XO----- X", Some((6, 6))).unwrap_err(),
+                   NotationError::InvalidLength { expected: 36, found: 16 });
+    }
+}
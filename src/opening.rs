@@ -0,0 +1,274 @@
+//! Other starts than [`Board::init`]: the diagonal start, XOT openings, balanced openings and random openings
+//!
+//! An opening is kept as the moves of the [`SavedGame`], so the game can be replayed from
+//! the usual start position.
+
+use std::fmt;
+use std::str::FromStr;
+
+use fastrand::Rng;
+
+use crate::*;
+
+/// The XOT list: balanced openings of 8 moves on a 8x8 board, one transcript per line
+///
+/// The published list is not included, the file must be filled before the XOT start can be used.
+pub const XOT_OPENINGS: &str = include_str!("../resources/openings/xot.txt");
+
+/// Openings of 8 moves on a 8x8 board that the engine find balanced, one transcript per line
+pub const BALANCED_OPENINGS: &str = include_str!("../resources/openings/balanced.txt");
+
+/// Number of moves of a random opening when it is not chosen
+pub const DEFAULT_RANDOM_MOVES: u8 = 6;
+
+/// Most predicted disc difference of a random opening to be balanced
+pub const RANDOM_BALANCE: i32 = 2;
+
+/// Number of moves the engine look ahead to verify the balance of a random opening
+const BALANCE_DEPTH: u8 = 4;
+
+/// Number of random openings tried before the most balanced one is kept
+const RANDOM_ATTEMPTS: usize = 50;
+
+/// How a game starts
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum StartPosition {
+    /// The four discs of [`Board::init`]
+    #[default]
+    Standard,
+    /// The black discs on the other diagonal (d4 and e5 on a 8x8 board)
+    Diagonal,
+    /// A random opening of the XOT list ([`XOT_OPENINGS`]), only on a 8x8 board
+    Xot,
+    /// A random opening of [`BALANCED_OPENINGS`], only on a 8x8 board
+    Balanced,
+    /// A random opening of this number of moves with a balanced evaluation
+    Random(u8),
+}
+
+impl StartPosition {
+    /// The starts proposed in the new game setup
+    pub const ALL: [StartPosition; 5] = [
+        StartPosition::Standard,
+        StartPosition::Diagonal,
+        StartPosition::Xot,
+        StartPosition::Balanced,
+        StartPosition::Random(DEFAULT_RANDOM_MOVES),
+    ];
+
+    /// Verify if the start can be used on a board of this size
+    pub fn is_available(&self, size: (i16, i16)) -> bool {
+        self.check(size).is_ok()
+    }
+
+    /// Verify if the start can be used on a board of this size, else return why
+    pub fn check(&self, size: (i16, i16)) -> Result<(), String> {
+        match self {
+            StartPosition::Xot | StartPosition::Balanced if size != (8, 8) =>
+                Err(format!("the {} start is only played on a 8x8 board", self)),
+            StartPosition::Xot if xot_openings().is_empty() =>
+                Err(String::from("the XOT list is empty, put the published list in resources/openings/xot.txt")),
+            _ => Ok(()),
+        }
+    }
+
+    /// Set the start of a game: the position of the diagonal start or the moves of an opening
+    ///
+    /// The moves of the game are replaced, except with the standard start.
+    pub fn apply(&self, game: SavedGame, rng: &mut Rng) -> SavedGame {
+        let size = (game.board.width, game.board.height);
+        match self {
+            StartPosition::Standard => game,
+            StartPosition::Diagonal => SavedGame { position: Some(diagonal_position(size)), moves: String::new(), ..game },
+            StartPosition::Xot => SavedGame { position: None, moves: xot_opening(rng), ..game },
+            StartPosition::Balanced => SavedGame { position: None, moves: balanced_opening(rng), ..game },
+            StartPosition::Random(moves) => SavedGame { position: None, moves: random_opening(size, *moves, rng), ..game },
+        }
+    }
+}
+
+/// Return the position string of the diagonal start, black to play
+pub fn diagonal_position(size: (i16, i16)) -> String {
+    let mut board = Board::new(size);
+    let (x, y) = (size.0 / 2 - 1, size.1 / 2 - 1);
+    board.set(GridPosition::new(x, y), Piece::BLACK);
+    board.set(GridPosition::new(x + 1, y + 1), Piece::BLACK);
+    board.set(GridPosition::new(x + 1, y), Piece::WHITE);
+    board.set(GridPosition::new(x, y + 1), Piece::WHITE);
+    board.to_position_string(Piece::BLACK)
+}
+
+/// Return the transcripts of a list of openings, the empty lines and the comments (`#`) are skipped
+fn parse_openings(list: &'static str) -> Vec<&'static str> {
+    list.lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty() && !line.starts_with('#'))
+        .collect()
+}
+
+/// Return the openings of [`XOT_OPENINGS`]
+pub fn xot_openings() -> Vec<&'static str> {
+    parse_openings(XOT_OPENINGS)
+}
+
+/// Choose an opening of [`XOT_OPENINGS`]
+///
+/// # Panics
+///
+/// Panics if the XOT list is empty (see [`StartPosition::check`])
+pub fn xot_opening(rng: &mut Rng) -> String {
+    let openings = xot_openings();
+    assert!(!openings.is_empty(), "The XOT list is empty");
+    openings[rng.usize(..openings.len())].to_string()
+}
+
+/// Return the openings of [`BALANCED_OPENINGS`]
+pub fn balanced_openings() -> Vec<&'static str> {
+    parse_openings(BALANCED_OPENINGS)
+}
+
+/// Choose an opening of [`BALANCED_OPENINGS`]
+pub fn balanced_opening(rng: &mut Rng) -> String {
+    let openings = balanced_openings();
+    openings[rng.usize(..openings.len())].to_string()
+}
+
+/// Play random moves from the standard start, the opening is kept when the engine find
+/// it balanced (see [`RANDOM_BALANCE`]), else the most balanced of some tries is returned
+///
+/// When every try ends the game (too many moves for the board), the last try is returned.
+pub fn random_opening(size: (i16, i16), moves: u8, rng: &mut Rng) -> String {
+    let engine = Engine::new(BALANCE_DEPTH);
+    let mut best: Option<(i32, String)> = None;
+    let mut last_finished = None;
+    for _ in 0..RANDOM_ATTEMPTS {
+        let mut board = Board::with_size(size);
+        let mut turn = Piece::BLACK;
+        let mut transcript = String::new();
+        for _ in 0..moves {
+            if !board.can_play(turn) {
+                turn = turn.next();
+            }
+            let valid_moves = board.get_valid_moves(turn);
+            if valid_moves.is_empty() {
+                break;
+            }
+            let position = valid_moves[rng.usize(..valid_moves.len())];
+            board.update(position, turn);
+            transcript += &position.to_notation();
+            turn = turn.next();
+        }
+        if board.is_finish() {
+            last_finished = Some(transcript);
+            continue;
+        }
+        let balance = engine.predict_difference(&board, turn).abs();
+        if balance <= RANDOM_BALANCE {
+            return transcript;
+        }
+        if best.as_ref().is_none_or(|(best_balance, _)| balance < *best_balance) {
+            best = Some((balance, transcript));
+        }
+    }
+    best.map(|(_, transcript)| transcript).or(last_finished).unwrap_or_default()
+}
+
+impl fmt::Display for StartPosition {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            StartPosition::Standard => write!(f, "Standard"),
+            StartPosition::Diagonal => write!(f, "Diagonal"),
+            StartPosition::Xot => write!(f, "XOT"),
+            StartPosition::Balanced => write!(f, "Balanced opening"),
+            StartPosition::Random(moves) => write!(f, "Random {} moves", moves),
+        }
+    }
+}
+
+impl FromStr for StartPosition {
+    type Err = String;
+
+    /// Read a start: "standard", "diagonal", "xot", "balanced", "random" or "random:N" (N moves)
+    fn from_str(name: &str) -> Result<Self, Self::Err> {
+        match name.trim().to_lowercase().as_str() {
+            "standard" => Ok(StartPosition::Standard),
+            "diagonal" => Ok(StartPosition::Diagonal),
+            "xot" => Ok(StartPosition::Xot),
+            "balanced" => Ok(StartPosition::Balanced),
+            "random" => Ok(StartPosition::Random(DEFAULT_RANDOM_MOVES)),
+            other => other.strip_prefix("random:")
+                .and_then(|moves| moves.parse::<u8>().ok())
+                .filter(|moves| *moves > 0)
+                .map(StartPosition::Random)
+                .ok_or_else(|| format!("\"{}\" is not a start: standard, diagonal, xot, balanced, random or random:N", name)),
+        }
+    }
+}
+
+#[allow(unreachable_code)]
+#[cfg(test)]
+mod tests {
+    use crate::*;
+
+    #[test]
+    fn starts() {
+        assert_eq!("Balanced".parse(), Ok(StartPosition::Balanced));
+        assert_eq!("XOT".parse(), Ok(StartPosition::Xot));
+        assert_eq!(StartPosition::Xot.to_string(), "XOT");
+        assert!(StartPosition::Xot.check((10, 10)).unwrap_err().contains("8x8"));
+        assert_eq!("random:10".parse(), Ok(StartPosition::Random(10)));
+        assert!("random:0".parse::<StartPosition>().is_err());
+        assert_eq!(StartPosition::Random(4).to_string(), "Random 4 moves");
+        assert!(!StartPosition::Balanced.is_available((10, 10)));
+
+        let mut rng = fastrand::Rng::with_seed(7);
+        let game = SavedGame { moves: String::from("f5"), ..SavedGame::default() };
+        assert_eq!(StartPosition::Standard.apply(game.clone(), &mut rng), game);
+        let game = StartPosition::Diagonal.apply(game, &mut rng);
+        assert_eq!(game.position.as_deref(), Some("---------------------------XO------OX--------------------------- X"));
+        assert_eq!(game.moves, "");
+        let (board, _) = game.start().unwrap();
+        assert_eq!(board.get_valid_moves(Piece::BLACK).len(), 4);
+    }
+
+    #[test]
+    fn xot() {
+        let openings = xot_openings();
+        assert_eq!(StartPosition::Xot.is_available((8, 8)), !openings.is_empty());
+        for transcript in openings {
+            let moves = parse_transcript(transcript).unwrap();
+            assert_eq!(moves.len(), 8, "{}", transcript);
+            assert!(play_transcript(&mut Board::default(), Piece::BLACK, &moves).is_ok(), "{}", transcript);
+        }
+    }
+
+    #[test]
+    fn balanced() {
+        let openings = balanced_openings();
+        assert!(openings.len() > 10);
+        for transcript in openings {
+            let moves = parse_transcript(transcript).unwrap();
+            assert_eq!(moves.len(), 8, "{}", transcript);
+            assert!(play_transcript(&mut Board::default(), Piece::BLACK, &moves).is_ok(), "{}", transcript);
+        }
+    }
+
+    #[test]
+    fn random() {
+        let mut rng = fastrand::Rng::with_seed(42);
+        let game = StartPosition::Random(4).apply(SavedGame::default(), &mut rng);
+        let moves = game.moves().unwrap();
+        assert_eq!(moves.len(), 4);
+        let (board, turn) = game.replay().unwrap();
+        assert!(Engine::new(4).predict_difference(&board, turn).abs() <= RANDOM_BALANCE);
+
+        let game = SavedGame { board: BoardConfig { width: 6, height: 6 }, ..SavedGame::default() };
+        assert_eq!(StartPosition::Random(3).apply(game, &mut rng).moves().unwrap().len(), 3);
+
+        // Every try ends the game on a 4x4 board, the last one is kept
+        let transcript = random_opening((4, 4), 20, &mut rng);
+        let mut board = Board::with_size((4, 4));
+        play_transcript(&mut board, Piece::BLACK, &parse_transcript(&transcript).unwrap()).unwrap();
+        assert!(board.is_finish());
+    }
+}
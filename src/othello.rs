@@ -1,54 +1,223 @@
 //! The main module that manage the game logic
 
-use ggez::{Context, event, GameResult};
-use ggez::event::{KeyCode, MouseButton};
+use std::io::Write;
+use std::path::PathBuf;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use ggez::{Context, filesystem, GameResult, timer};
+use ggez::event::{KeyCode, KeyMods, MouseButton};
 use ggez::graphics::{self, Color, Rect};
 use log::{debug, error, info};
 
 use crate::*;
 
+/// Number of moves the engine look ahead to evaluate the position shown beside the board
+const EVALUATION_DEPTH: u8 = 3;
+
 /// Main structure of the game that hold all state necessary to play
 pub struct Othello {
     board: Board,
+    start_board: Board,
+    start_turn: Piece,
+    /// The played moves and the passes
+    moves: Vec<Move>,
+    /// The last played move, animated then marked
+    last_move: Option<MoveAnimation>,
     player_black: Player,
     player_white: Player,
     current_player: Player,
     current_move: Option<GridPosition>,
     current_player_has_played: bool,
-    gameover: bool,
+    /// The cell chosen with the keyboard, hidden until a key move it
+    cursor: Option<GridPosition>,
+    /// The sound effects to play on the next update
+    sounds: Vec<Sound>,
+    state: GameState,
+    /// The popup with the result was shown
+    result_shown: bool,
+    /// The positions are evaluated by the engine, only when the evaluation is shown
+    evaluation_enabled: bool,
+    /// Predicted final disc difference for black at the start and after each played move
+    evaluations: Vec<i32>,
+    /// Time used by each player (black, white)
+    clocks: (Duration, Duration),
+    /// Time of each player for the whole game, unlimited if `None`
+    time_limit: Option<Duration>,
     theme: Theme,
+    save_path: Option<PathBuf>,
 }
 
 impl Othello {
     /// Create a new game
     pub fn new(player_black: Player, player_white: Player) -> Self {
-        Othello {
+        let mut othello = Othello {
             board: Board::default(),
+            start_board: Board::default(),
+            start_turn: Piece::BLACK,
+            moves: Vec::new(),
+            last_move: None,
+            current_player: player_black.clone(),
             player_black,
             player_white,
-            current_player: player_black,
             current_move: None,
             current_player_has_played: false,
-            gameover: false,
-            theme: DEFAULT_THEME,
-        }
+            cursor: None,
+            sounds: Vec::new(),
+            state: GameState::InProgress(Piece::BLACK),
+            result_shown: false,
+            evaluation_enabled: true,
+            evaluations: Vec::new(),
+            clocks: (Duration::ZERO, Duration::ZERO),
+            time_limit: None,
+            theme: Theme::default(),
+            save_path: SavedGame::default_path(),
+        };
+        othello.refresh_evaluations();
+        othello
     }
 
-    /// Reset the game
+    /// Reset the game to its start position
     fn reset(&mut self) {
-        self.board.reset();
-        self.current_player = self.player_white;
+        self.board = self.start_board.clone();
+        self.current_player = self.player(self.start_turn).clone();
+        self.moves.clear();
+        self.last_move = None;
         self.current_move = None;
         self.current_player_has_played = false;
-        self.gameover = false;
+        self.state = GameState::InProgress(self.start_turn);
+        self.result_shown = false;
+        self.refresh_evaluations();
+        self.clocks = (Duration::ZERO, Duration::ZERO);
     }
 
-    /// Set the board of the game
+    /// Set the board of the game (its start position)
     pub fn set_board(mut self, board: Board) -> Self {
+        self.start_board = board.clone();
         self.board = board;
+        self.refresh_evaluations();
+        self
+    }
+
+    /// Set the player who have to play first
+    pub fn set_turn(mut self, piece: Piece) -> Self {
+        self.start_turn = piece;
+        self.current_player = self.player(piece).clone();
+        self.state = GameState::InProgress(piece);
+        self.refresh_evaluations();
+        self
+    }
+
+    /// Evaluate the positions with the engine or not, they are evaluated by default
+    pub fn set_evaluation(mut self, enabled: bool) -> Self {
+        self.enable_evaluation(enabled);
+        self
+    }
+
+    /// Set the time of each player for the whole game, a player lose when his time is over
+    pub fn set_time_limit(mut self, time_limit: Duration) -> Self {
+        self.time_limit = Some(time_limit);
+        self
+    }
+
+    /// Set the file where the game is saved with the keyboard shortcut
+    pub fn set_save_path(mut self, path: PathBuf) -> Self {
+        self.save_path = Some(path);
         self
     }
 
+    /// Resume a saved game: its start position, its moves, its clocks and its end
+    ///
+    /// The players and the theme are not changed.
+    pub fn resume(self, game: &SavedGame) -> Result<Self, NotationError> {
+        let (start_board, start_turn) = game.start()?;
+        let mut board = start_board.clone();
+        let mut turn = start_turn;
+        // The passes are not written in the transcript
+        let mut moves = Vec::new();
+        for (index, position) in game.moves()?.into_iter().enumerate() {
+            let piece = play_transcript_move(&mut board, turn, index, position)?;
+            if piece != turn {
+                moves.push(Move::Pass(turn));
+            }
+            moves.push(Move::Play(piece, position));
+            turn = piece.next();
+        }
+
+        let mut othello = self.set_board(start_board).set_turn(start_turn);
+        othello.board = board;
+        othello.current_player = othello.player(turn).clone();
+        othello.state = match game.end {
+            Some(end) => GameState::Finished(Outcome::new(&othello.board, end.reason())),
+            None => GameState::InProgress(turn),
+        };
+        // The last move is marked but not animated
+        othello.last_move = moves.iter().rev()
+            .find_map(|last_move| last_move.position().map(|position| (position, last_move.piece())))
+            .map(|(position, piece)| MoveAnimation::new(position, piece, Flips::default(), None));
+        othello.moves = moves;
+        othello.refresh_evaluations();
+        othello.clocks = (
+            Duration::try_from_secs_f64(game.clocks.black).unwrap_or_default(),
+            Duration::try_from_secs_f64(game.clocks.white).unwrap_or_default(),
+        );
+        Ok(othello)
+    }
+
+    /// Describe the game so it can be saved
+    pub fn saved_game(&self) -> SavedGame {
+        let (width, height) = self.start_board.size();
+        let is_standard_start = self.start_turn == Piece::BLACK
+            && self.start_board == Board::with_size((width, height));
+        SavedGame {
+            moves: write_transcript(&self.moves.iter().filter_map(Move::position).collect::<Vec<_>>()),
+            position: (!is_standard_start).then(|| self.start_board.to_position_string(self.start_turn)),
+            end: match self.state {
+                GameState::Finished(outcome) => GameEnd::from_reason(outcome.reason),
+                _ => None,
+            },
+            clocks: Clocks {
+                black: self.clocks.0.as_secs_f64(),
+                white: self.clocks.1.as_secs_f64(),
+            },
+            black: PlayerConfig::from(&self.player_black),
+            white: PlayerConfig::from(&self.player_white),
+            board: BoardConfig { width, height },
+            theme: Some(ThemeConfig::from(&self.theme)),
+        }
+    }
+
+    /// Save the game in the file of the keyboard shortcut
+    fn save(&self) {
+        match &self.save_path {
+            Some(path) => self.save_to(path.clone()),
+            None => error!("No directory to save the game"),
+        }
+    }
+
+    /// Save the game in the autosave file
+    pub fn autosave(&self) {
+        match SavedGame::autosave_path() {
+            Some(path) => self.save_to(path),
+            None => error!("No directory to save the game"),
+        }
+    }
+
+    /// Save the game in a file
+    fn save_to(&self, path: PathBuf) {
+        match self.saved_game().save(&path) {
+            Ok(()) => info!("The game is saved in {}", path.display()),
+            Err(err) => error!("{}", err),
+        }
+    }
+
+    /// Return the player of a color
+    fn player(&self, piece: Piece) -> &Player {
+        match piece {
+            Piece::WHITE => &self.player_white,
+            _ => &self.player_black,
+        }
+    }
+
     /// Set the theme of the game
     pub fn set_theme(mut self, theme: Theme) -> Self {
         self.theme = theme;
@@ -56,46 +225,249 @@ impl Othello {
     }
 
 
-    /// Ask if the game is over
-    fn is_over(&self) -> bool {
-        self.board.is_finish()
+    /// Return the state of the game
+    pub fn state(&self) -> GameState {
+        self.state
     }
 
-    /// action to do when the user click
-    fn click(&mut self, x: f32, y: f32) {
-        let pos = GridPosition::from_screen(x, y);
-        if pos.in_screen() {
-            let grid_pos = pos.into_grid();
-            if self.board.is_valid_move(grid_pos, self.current_player.piece) {
-                info!("The player {} play at {}", self.current_player, grid_pos);
-                // self.board.update(grid_pos, self.current_player);
-                self.current_move = Some(grid_pos);
-
-                // Notify that the player has played.
-                // We do it here because we may have a menu on the side,
-                // so players will could click without playing
-                self.current_player_has_played = true;
-            } else {
-                info!("The position {} is not valid for {}", grid_pos, self.current_player)
+    /// Return the played moves and the passes
+    pub fn moves(&self) -> &[Move] {
+        &self.moves
+    }
+
+    /// Return the predicted final disc difference for black on the current board
+    ///
+    /// `None` when the evaluation is disabled (see [`Othello::set_evaluation`]).
+    pub fn evaluation(&self) -> Option<i32> {
+        let played = self.moves.iter().filter(|played| played.position().is_some()).count();
+        self.evaluations.get(played).copied()
+    }
+
+    /// Start or stop the evaluation of the positions, the played positions are evaluated when it starts
+    fn enable_evaluation(&mut self, enabled: bool) {
+        if self.evaluation_enabled != enabled {
+            self.evaluation_enabled = enabled;
+            self.refresh_evaluations();
+        }
+    }
+
+    /// Evaluate again every position from the start to the current board, nothing if the evaluation is disabled
+    fn refresh_evaluations(&mut self) {
+        self.evaluations.clear();
+        if !self.evaluation_enabled {
+            return;
+        }
+        let mut board = self.start_board.clone();
+        self.evaluations.push(evaluate(&board, self.start_turn));
+        let played = self.moves.iter().filter_map(|played| played.position().map(|position| (played.piece(), position)));
+        for (piece, position) in played {
+            board.update(position, piece);
+            self.evaluations.push(evaluate(&board, piece.next()));
+        }
+    }
+
+    /// The current player resign, the game is over
+    pub fn resign(&mut self) {
+        if let Some(piece) = self.state.turn() {
+            info!("The player {} resign", self.player(piece));
+            self.finish(EndReason::Resignation(piece));
+        }
+    }
+
+    /// Verify then play a move of the player `piece`
+    ///
+    /// The game is over if no player can play after the move,
+    /// and the other player pass if he cannot play.
+    pub fn play(&mut self, piece: Piece, position: GridPosition) -> Result<MoveOutcome, OthelloError> {
+        let turn = self.state.turn().ok_or(OthelloError::GameOver)?;
+        if piece != turn {
+            return Err(OthelloError::WrongTurn { expected: turn, found: piece });
+        }
+        let flips = self.board.play(position, piece)?;
+        self.moves.push(Move::Play(piece, position));
+        if self.evaluation_enabled {
+            self.evaluations.push(evaluate(&self.board, piece.next()));
+        }
+        self.sounds.push(Sound::Place);
+        self.sounds.push(Sound::Flip(flips.len()));
+        self.last_move = Some(MoveAnimation::new(position, piece, flips.clone(), self.theme.animation_duration));
+        self.current_move = None;
+        self.current_player_has_played = false;
+        self.current_player = self.player(piece.next()).clone();
+        self.state = GameState::InProgress(piece.next());
+        self.check_turn();
+        Ok(MoveOutcome { flips, state: self.state })
+    }
+
+    /// End the game if no player can play, or let the current player pass if he cannot play
+    fn check_turn(&mut self) {
+        let piece = match self.state {
+            GameState::InProgress(piece) => piece,
+            _ => return,
+        };
+        if self.board.is_finish() {
+            self.finish(EndReason::NoMoreMoves);
+        } else if !self.board.can_play(piece) {
+            info!("The player {} cannot play and pass", self.current_player);
+            self.moves.push(Move::Pass(piece));
+            self.sounds.push(Sound::Pass);
+            self.current_player = self.player(piece.next()).clone();
+            self.state = GameState::Pass(piece);
+        }
+    }
+
+    /// End the game
+    fn finish(&mut self, reason: EndReason) {
+        let outcome = Outcome::new(&self.board, reason);
+        info!("The game is over: {}", outcome);
+        self.state = GameState::Finished(outcome);
+        self.sounds.push(Sound::GameEnd);
+        self.current_move = None;
+        self.current_player_has_played = false;
+    }
+
+    /// Advance the game, `delta` is the time elapsed since the last step
+    fn step(&mut self, delta: Duration) {
+        if let Some(last_move) = &mut self.last_move {
+            last_move.update(delta);
+        }
+        let piece = match self.state.turn() {
+            Some(piece) => piece,
+            None => return,
+        };
+
+        // The clock of the current player is running
+        let clock = match piece {
+            Piece::WHITE => &mut self.clocks.1,
+            _ => &mut self.clocks.0,
+        };
+        *clock += delta;
+
+        if self.time_limit.is_some_and(|time_limit| *clock > time_limit) {
+            self.finish(EndReason::Timeout(piece));
+            return;
+        }
+        // The start position may be finished or the first player may have to pass
+        self.check_turn();
+
+        if let Some(position) = self.current_move.take().filter(|_| self.current_player_has_played) {
+            if let Err(err) = self.play(self.current_player.piece, position) {
+                error!("{}", err);
+                self.current_player_has_played = false;
             }
+        } else if let PlayerKind::Computer(engine) = self.current_player.kind {
+            // The computer wait the end of the animation of the last move
+            let is_animated = self.last_move.as_ref().is_some_and(MoveAnimation::is_running);
+            if !self.state.is_finished() && !is_animated {
+                self.computer_play(engine);
+            }
+        }
+    }
+
+    /// action to do when the user click
+    fn click(&mut self, ctx: &Context, x: f32, y: f32) {
+        // Only the grid is clickable, not the coordinates around it
+        let mut layout = Layout::from_context(ctx);
+        layout.board = self.theme.grid_area(layout.board);
+        if let Some(pos) = layout.board_position(x, y) {
+            let grid_pos = pos.into_grid(self.board.cell_size((layout.board.w, layout.board.h)));
+            self.choose(grid_pos);
         } else {
-            error!("The user click cannot be out of the screen");
+            debug!("The click ({}, {}) is not on the board", x, y);
+        }
+    }
+
+    /// The human player choose to play at `grid_pos` with the mouse or the keyboard
+    fn choose(&mut self, grid_pos: GridPosition) {
+        if !self.board.in_board(grid_pos) {
+            debug!("The position {} is out of the board", grid_pos);
+        } else if self.board.is_valid_move(grid_pos, self.current_player.piece) {
+            info!("The player {} play at {}", self.current_player, grid_pos);
+            // self.board.update(grid_pos, self.current_player);
+            self.current_move = Some(grid_pos);
+
+            // Notify that the player has played.
+            // We do it here because the side panel is not a part of the board,
+            // so players could click without playing
+            self.current_player_has_played = true;
+        } else {
+            info!("The position {} is not valid for {}", grid_pos, self.current_player);
+            self.sounds.push(Sound::Illegal);
+        }
+    }
+
+    /// Move the keyboard cursor, it appears in the middle of the board the first time
+    fn move_cursor(&mut self, dx: i16, dy: i16) {
+        let (width, height) = self.board.size();
+        self.cursor = Some(match self.cursor {
+            Some(cursor) => GridPosition::new((cursor.x + dx).clamp(0, width - 1), (cursor.y + dy).clamp(0, height - 1)),
+            None => GridPosition::new(width / 2 - 1, height / 2 - 1),
+        });
+    }
+
+    /// Move the keyboard cursor on the next valid move (the previous one if `forward` is false)
+    ///
+    /// The valid moves are cycled from the top left to the bottom right.
+    fn cycle_valid_moves(&mut self, forward: bool) {
+        let moves = self.board.get_valid_moves(self.current_player.piece);
+        let order = |position: &GridPosition| (position.y, position.x);
+        let next = match self.cursor {
+            Some(cursor) if forward => moves.iter().find(|position| order(position) > order(&cursor)).or(moves.first()),
+            Some(cursor) => moves.iter().rev().find(|position| order(position) < order(&cursor)).or(moves.last()),
+            None => moves.first(),
+        };
+        if let Some(position) = next {
+            self.cursor = Some(*position);
         }
     }
 
-    /// Return the next player who have to play
-    fn next_player(&mut self) -> Player {
-        match self.current_player.piece {
-            Piece::BLACK => self.player_white,
-            Piece::WHITE => self.player_black,
-            Piece::EMPTY => panic!("A player cannot have the Piece::EMPTY"),
+    /// Play at the keyboard cursor
+    fn play_cursor(&mut self) {
+        match self.cursor {
+            Some(cursor) if self.current_player.is_human() && !self.state.is_finished() => self.choose(cursor),
+            Some(_) => debug!("The player {} cannot play with the keyboard", self.current_player),
+            None => self.move_cursor(0, 0),
+        }
+    }
+
+    /// Save a screenshot of the window in the user data directory
+    fn screenshot(&self, ctx: &mut Context) -> GameResult {
+        let seconds = SystemTime::now().duration_since(UNIX_EPOCH).unwrap_or_default().as_secs();
+        let path = format!("/screenshot-{}.png", seconds);
+        graphics::screenshot(ctx)?.encode(ctx, graphics::ImageFormat::Png, &path)?;
+        info!("SCREENSHOT saved in {}", path);
+        Ok(())
+    }
+
+    /// Save a diagram of the board in PNG and in SVG in the user data directory
+    ///
+    /// The moves are numbered and the last one is highlighted.
+    fn export_diagram(&self, ctx: &mut Context) -> GameResult {
+        let moves: Vec<GridPosition> = self.moves.iter().filter_map(Move::position).collect();
+        let diagram = Diagram::new(&self.board)
+            .set_theme(self.theme.clone())
+            .set_moves(&moves)
+            .set_highlights(&moves.last().copied().into_iter().collect::<Vec<_>>());
+        let name = format!("/diagram-{}", moves.len());
+        diagram.save_png(ctx, format!("{}.png", name))?;
+        filesystem::create(ctx, format!("{}.svg", name))?.write_all(diagram.to_svg().as_bytes())?;
+        info!("DIAGRAM saved in {}.png and {}.svg", name, name);
+        Ok(())
+    }
+
+    /// Let the computer choose the move of the current player
+    fn computer_play(&mut self, engine: Engine) {
+        if let Some(position) = engine.best_move(&self.board, self.current_player.piece) {
+            info!("The computer {} play at {}", self.current_player, position);
+            self.current_move = Some(position);
+            self.current_player_has_played = true;
         }
     }
 
     /// Draw the score on the screen
     ///
     /// Return (black_score, white_score)
-    fn score(&self) -> (u8, u8) {
+    fn score(&self) -> (u16, u16) {
         let black_score = self.board.score(self.player_black.piece);
         let white_score = self.board.score(self.player_white.piece);
         debug!("the score is:\n\t{}: {}\n\t{}: {}",
@@ -105,109 +477,438 @@ impl Othello {
         (black_score, white_score)
     }
 
-    /// Draw the score on the screen
-    fn draw_score(&self, ctx: &mut Context) -> GameResult {
-        let (score_black, score_white) = self.score();
+    /// Describe the result of a game, e.g. "Marie Curie (WHITE) win by resignation"
+    fn result_text(&self, outcome: &Outcome) -> String {
+        match (outcome.winner, outcome.reason) {
+            (None, _) => String::from("Draw"),
+            (Some(winner), EndReason::NoMoreMoves) => format!("{} win", self.player(winner)),
+            (Some(winner), EndReason::Resignation(_)) => format!("{} win by resignation", self.player(winner)),
+            (Some(winner), EndReason::Timeout(_)) => format!("{} win on time", self.player(winner)),
+        }
+    }
+
+    /// Write the moves two by two (black and white), e.g. "1. f5 d6"
+    ///
+    /// The first line is "1. ... f4" when white played the first move.
+    fn move_list(&self) -> Vec<String> {
+        let white_first = self.moves.first().is_some_and(|first| first.piece() == Piece::WHITE);
+        let moves: Vec<String> = white_first.then(|| String::from("..."))
+            .into_iter()
+            .chain(self.moves.iter().map(Move::to_string))
+            .collect();
+        moves.chunks(2)
+            .enumerate()
+            .map(|(index, moves)| format!("{:>2}. {}", index + 1, moves.join(" ")))
+            .collect()
+    }
+
+    /// Write the content of the side panel, only the last `move_lines` lines of the move list are written
+    fn panel_text(&self, move_lines: usize) -> String {
+        let mut text = String::new();
+        for (piece, clock) in [(Piece::BLACK, self.clocks.0), (Piece::WHITE, self.clocks.1)] {
+            let marker = if self.state.turn() == Some(piece) { ">" } else { " " };
+            text += &format!("{} {}\n  {} discs  {:02}:{:02}\n\n",
+                marker,
+                self.player(piece),
+                self.board.score(piece),
+                clock.as_secs() / 60,
+                clock.as_secs() % 60,
+            );
+        }
+        text += &match self.state {
+            GameState::InProgress(piece) => format!("{} to play", piece),
+            GameState::Pass(piece) => format!("{} passed", piece),
+            GameState::Finished(outcome) => self.result_text(&outcome),
+        };
+
+        text += "\n\nMoves:";
+        let move_list = self.move_list();
+        for line in &move_list[move_list.len().saturating_sub(move_lines)..] {
+            text += "\n";
+            text += line;
+        }
+        text
+    }
 
-        // Draw the background of the popup
-        let popup = graphics::MeshBuilder::new()
-            .rounded_rectangle(
+    /// Draw the side panel: the players, their score, the turn and the moves
+    ///
+    /// With `show_evaluation` the evaluation bar is drawn on the side of the board
+    /// and the evaluation graph at the bottom of the panel.
+    fn draw_panel(&self, ctx: &mut Context, layout: &Layout, show_evaluation: bool) -> GameResult {
+        let mut area = layout.panel;
+        let background = graphics::MeshBuilder::new()
+            .rectangle(graphics::DrawMode::fill(), area, Color::new(0.0, 0.0, 0.0, 0.6))?
+            .build(ctx)?;
+        graphics::draw(ctx, &background, graphics::DrawParam::default())?;
+
+        let font_size = layout.font_size(self.theme.font_scale);
+        let margin = 10.0 * layout.scale;
+        let font = graphics::Font::new(ctx, self.theme.font_path.as_ref())?;
+        if show_evaluation {
+            area = self.draw_evaluation(ctx, layout, font, font_size)?;
+        }
+
+        // The header use 9 lines, the move list use the rest of the panel
+        let line_height = 1.2 * font_size;
+        let move_lines = ((area.h - 2.0 * margin) / line_height) as usize;
+        let mut text = graphics::Text::new((self.panel_text(move_lines.saturating_sub(9)), font, font_size));
+        text.set_bounds(glam::Vec2::new(area.w - 2.0 * margin, area.h - 2.0 * margin), graphics::Align::Left);
+        graphics::draw(ctx, &text, (glam::Vec2::new(area.x + margin, area.y + margin), Color::WHITE))?;
+        Ok(())
+    }
+
+    /// Draw the evaluation bar on the left of the panel and the graph at its bottom
+    ///
+    /// The black part of the bar grow with the predicted disc difference for black.
+    /// Return the rest of the panel.
+    fn draw_evaluation(&self, ctx: &mut Context, layout: &Layout, font: graphics::Font, font_size: f32) -> GameResult<Rect> {
+        let area = layout.panel;
+        let margin = 10.0 * layout.scale;
+        let bar_width = 12.0 * layout.scale;
+        let (width, height) = self.board.size();
+        let cases = width as i32 * height as i32;
+        let difference = self.evaluation().unwrap_or(0);
+
+        let bar = Rect::new(area.x, area.y, bar_width, area.h);
+        let black_height = bar.h * (0.5 + difference as f32 / (2.0 * cases as f32)).clamp(0.0, 1.0);
+        let mesh = graphics::MeshBuilder::new()
+            .rectangle(graphics::DrawMode::fill(), bar, self.theme.piece_colors.1)?
+            .rectangle(graphics::DrawMode::fill(), Rect::new(bar.x, bar.y, bar.w, black_height), self.theme.piece_colors.0)?
+            .line(&[glam::Vec2::new(bar.left(), bar.y + bar.h / 2.0), glam::Vec2::new(bar.right(), bar.y + bar.h / 2.0)], 1.0, self.theme.grid_color)?
+            .build(ctx)?;
+        graphics::draw(ctx, &mesh, graphics::DrawParam::default())?;
+
+        // The graph use the bottom fifth of the panel, under its caption
+        let graph_height = area.h / 5.0;
+        let graph = Rect::new(bar.right() + margin, area.bottom() - graph_height, area.right() - bar.right() - 2.0 * margin, graph_height - margin);
+        draw_evaluation_graph(ctx, &self.theme, graph, &self.evaluations, cases, None)?;
+        let caption = match difference {
+            0 => String::from("Evaluation: even"),
+            difference if difference > 0 => format!("Evaluation: {} +{}", Piece::BLACK, difference),
+            difference => format!("Evaluation: {} +{}", Piece::WHITE, -difference),
+        };
+        let text = graphics::Text::new((caption, font, font_size));
+        let caption_y = graph.y - 1.2 * font_size - margin / 2.0;
+        graphics::draw(ctx, &text, (glam::Vec2::new(graph.x, caption_y), Color::WHITE))?;
+
+        Ok(Rect::new(bar.right(), area.y, area.w - bar_width, caption_y - area.y))
+    }
+
+    /// Announce on the top of the board that a player passed
+    fn draw_pass(&self, ctx: &mut Context, layout: &Layout, piece: Piece) -> GameResult {
+        let area = layout.board;
+        let margin = 10.0 * layout.scale;
+        let message = format!("{} cannot play and pass", self.player(piece));
+        let font = graphics::Font::new(ctx, self.theme.font_path.as_ref())?;
+        let text = graphics::Text::new((message, font, layout.font_size(self.theme.font_scale)));
+        let dimensions = text.dimensions(ctx);
+        let x = area.x + (area.w - dimensions.w) / 2.0;
+
+        let background = graphics::MeshBuilder::new()
+            .rectangle(
                 graphics::DrawMode::fill(),
-                Rect::new(
-                    SCREEN_SIZE.0 as f32 / 4.0,
-                    SCREEN_SIZE.1 as f32 / 4.0,
-                    SCREEN_SIZE.0 as f32 / 2.0,
-                    SCREEN_SIZE.1 as f32 / 2.0,
-                ),
-                50.0,
-                Color::new(0.5, 0.5, 0.5, 1.0)
+                Rect::new(x - margin, area.y, dimensions.w + 2.0 * margin, dimensions.h + margin),
+                Color::new(0.0, 0.0, 0.0, 0.6),
             )?
             .build(ctx)?;
-        graphics::draw(ctx, &popup, graphics::DrawParam::default())?;
+        graphics::draw(ctx, &background, graphics::DrawParam::default())?;
+        graphics::draw(ctx, &text, (glam::Vec2::new(x, area.y + margin / 2.0), Color::WHITE))?;
+        Ok(())
+    }
 
-        // Set and draw the text in the popup
-        let text_format = format!("{}: {}\n\t{}: {}",
+    /// Return the text of the popup shown at the end of the game
+    fn score_text(&self, outcome: &Outcome) -> String {
+        let (score_black, score_white) = outcome.score;
+        format!("{}\n\n{}: {}\n\t{}: {}\n\nCtrl+A: analysis of the game",
+            self.result_text(outcome),
             self.player_black, score_black,
             self.player_white, score_white
-        );
+        )
+    }
 
-        let font = graphics::Font::new(ctx, self.theme.font_path)?;
-        let text = graphics::Text::new((text_format.as_str(), font, self.theme.font_scale));
-        let dest_point = glam::Vec2::new(300.0, 300.0);
-        graphics::draw(ctx, &text, (dest_point,))?;
+    /// Evaluate every move of the game with the engine and show the report
+    fn analyze(&self) -> Transition {
+        match Analysis::new(&self.saved_game(), Engine::new(DEFAULT_ANALYSIS_DEPTH)) {
+            Ok(analysis) => {
+                info!("{}", analysis.summary(Piece::BLACK));
+                info!("{}", analysis.summary(Piece::WHITE));
+                Transition::Push(Box::new(AnalysisView::new(analysis, self.theme.clone())))
+            }
+            Err(err) => {
+                error!("Cannot analyze the game: {}", err);
+                Transition::None
+            }
+        }
+    }
+}
 
-        Ok(())
+/// Return the predicted final disc difference for black, `piece` is the side to move
+fn evaluate(board: &Board, piece: Piece) -> i32 {
+    let engine = Engine::new(EVALUATION_DEPTH);
+    match piece {
+        Piece::WHITE => -engine.predict_difference(board, Piece::WHITE),
+        _ => engine.predict_difference(board, Piece::BLACK),
     }
 }
 
-impl event::EventHandler<ggez::GameError> for Othello {
+impl Scene for Othello {
     /// Update will happen on every frame before it is drawn.
-    fn update(&mut self, _ctx: &mut Context) -> GameResult {
-        if ! self.gameover {
-            if self.is_over() {
-                self.gameover = true;
-            } else if self.current_player_has_played {
-                self.board.update(
-                    self.current_move.unwrap(),
-                    self.current_player.piece,
-                );
-                self.current_player_has_played = false;
-                self.current_player = self.next_player();
-            } else if !self.board.can_play(self.current_player.piece) {
-                self.current_player = self.next_player();
+    ///
+    /// When the game is over a popup show the score.
+    fn update(&mut self, ctx: &mut Context, world: &mut World) -> GameResult<Transition> {
+        self.step(timer::delta(ctx));
+        for sound in self.sounds.drain(..) {
+            world.play_sound(ctx, sound);
+        }
+        self.enable_evaluation(world.settings.evaluation);
+        if let GameState::Finished(outcome) = self.state {
+            if !self.result_shown && !self.last_move.as_ref().is_some_and(MoveAnimation::is_running) {
+                self.result_shown = true;
+                return Ok(Transition::Push(Box::new(Popup::new(self.score_text(&outcome)))));
             }
         }
-        Ok(())
+        Ok(Transition::None)
     }
 
     /// Render the game's current state.
-    fn draw(&mut self, ctx: &mut Context) -> GameResult {
+    fn draw(&mut self, ctx: &mut Context, world: &World) -> GameResult {
         // First we clear the screen and set the background color
         graphics::clear(ctx, self.theme.background_color);
 
-        // Draw the board and his content
-        self.board.draw(ctx, self.current_player.piece, self.theme)?;
+        // Draw the board and his content on the left, the side panel on the right
+        let layout = Layout::from_context(ctx);
+        self.board.draw_animated(ctx, layout.board, self.current_player.piece, &self.theme, self.last_move.as_ref())?;
+        self.draw_panel(ctx, &layout, world.settings.evaluation)?;
 
-        // If the game is over draw a popup to show the score
-        if self.gameover {
-            self.draw_score(ctx)?;
+        if let Some(cursor) = self.cursor {
+            self.board.draw_cursor(ctx, &self.theme, cursor, layout.board)?;
         }
 
-        // Finally we call graphics::present to cycle the gpu's framebuffer and display
-        // the new frame we just drew.
-        graphics::present(ctx)?;
-
-        // And return success.
+        // Announce the pass until the other player play
+        if let GameState::Pass(piece) = self.state {
+            self.draw_pass(ctx, &layout, piece)?;
+        }
         Ok(())
     }
 
     /// Called every time a mouse button gets pressed
-    fn mouse_button_down_event(
-        &mut self,
-        _ctx: &mut Context,
-        button: MouseButton,
-        x: f32,
-        y: f32
-    ) {
-        if button == MouseButton::Left {
-            self.click(x, y);
+    fn mouse_down(&mut self, ctx: &mut Context, _world: &mut World, button: MouseButton, x: f32, y: f32) -> Transition {
+        // The moves of a computer player are not chosen by the user
+        if button == MouseButton::Left && self.current_player.is_human() && !self.state.is_finished() {
+            self.click(ctx, x, y);
         }
+        Transition::None
     }
 
     /// Called every time a key gets pressed
     /// Inputs are managed here
-    fn key_down_event(
-        &mut self,
-        ctx: &mut Context,
-        keycode: KeyCode,
-        _keymod: ggez::input::keyboard::KeyMods,
-        _repeat: bool,
-    ) {
+    fn key_down(&mut self, ctx: &mut Context, world: &mut World, keycode: KeyCode, keymod: KeyMods) -> Transition {
         match keycode {
-            KeyCode::Escape => {info!("EXIT from key Escape"); event::quit(ctx);},
-            KeyCode::R => {debug!("RESET from key R"); self.reset();},
-            KeyCode::S => {debug!("SCORE from key S"); self.score();},
+            KeyCode::F12 => if let Err(err) = self.screenshot(ctx) { error!("Cannot save the screenshot: {}", err) },
+            KeyCode::D if keymod.contains(KeyMods::CTRL) => {
+                if let Err(err) = self.export_diagram(ctx) { error!("Cannot save the diagram: {}", err) }
+            },
+            KeyCode::Escape => {info!("LEAVE the game from key Escape"); return Transition::Pop;},
+            KeyCode::A if keymod.contains(KeyMods::CTRL) => {debug!("ANALYZE from keys Ctrl+A"); return self.analyze();},
+            KeyCode::S if keymod.contains(KeyMods::CTRL) => {debug!("SAVE from keys Ctrl+S"); self.save();},
+            KeyCode::Q if keymod.contains(KeyMods::CTRL) => {debug!("RESIGN from keys Ctrl+Q"); self.resign();},
+            KeyCode::R if keymod.contains(KeyMods::CTRL) => {debug!("RESET from keys Ctrl+R"); self.reset();},
+            KeyCode::T if keymod.contains(KeyMods::CTRL) => self.theme = world.next_theme().clone(),
+            KeyCode::P => {debug!("SCORE from key P"); self.score();},
+            KeyCode::V => world.settings.evaluation = !world.settings.evaluation,
+            KeyCode::Up | KeyCode::W => self.move_cursor(0, -1),
+            KeyCode::Down | KeyCode::S => self.move_cursor(0, 1),
+            KeyCode::Left | KeyCode::A => self.move_cursor(-1, 0),
+            KeyCode::Right | KeyCode::D => self.move_cursor(1, 0),
+            KeyCode::Tab => self.cycle_valid_moves(!keymod.contains(KeyMods::SHIFT)),
+            KeyCode::Return | KeyCode::NumpadEnter | KeyCode::Space => self.play_cursor(),
             _ => {}
         };
+        Transition::None
+    }
+
+    /// Called when the game is left or when the window is closed: the game is saved
+    fn leave(&mut self, _world: &mut World) {
+        self.autosave();
+    }
+}
+
+#[allow(unreachable_code)]
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use crate::*;
+
+    fn game(position: Option<&str>, moves: &str) -> Othello {
+        let game = SavedGame {
+            moves: String::from(moves),
+            position: position.map(String::from),
+            ..SavedGame::default()
+        };
+        let (player_black, player_white) = game.players();
+        Othello::new(player_black, player_white).resume(&game).unwrap()
+    }
+
+    #[test]
+    fn resign() {
+        let mut othello = game(None, "f5d6");
+        assert_eq!(othello.state(), GameState::InProgress(Piece::BLACK));
+        othello.resign();
+        assert_eq!(othello.state(), GameState::Finished(Outcome {
+            winner: Some(Piece::WHITE),
+            score: (3, 3),
+            reason: EndReason::Resignation(Piece::BLACK),
+        }));
+        othello.step(Duration::from_secs(1));
+        assert_eq!(othello.saved_game().clocks.black, 0.0);
+
+        // The resignation is kept when the game is resumed
+        let game = othello.saved_game();
+        assert_eq!(game.end, Some(GameEnd::BlackResigned));
+        let (player_black, player_white) = game.players();
+        let resumed = Othello::new(player_black, player_white).resume(&game).unwrap();
+        assert_eq!(resumed.state(), othello.state());
+    }
+
+    #[test]
+    fn timeout() {
+        let mut othello = game(None, "").set_time_limit(Duration::from_secs(10));
+        othello.step(Duration::from_secs(10));
+        assert_eq!(othello.state(), GameState::InProgress(Piece::BLACK));
+        othello.step(Duration::from_secs(1));
+        match othello.state() {
+            GameState::Finished(outcome) => assert_eq!(outcome.reason, EndReason::Timeout(Piece::BLACK)),
+            state => panic!("The game is not over: {:?}", state),
+        }
+    }
+
+    #[test]
+    fn pass_and_finish() {
+        // Black cannot play, white can play at d1
+        let mut othello = game(Some("OXX------------- X"), "");
+        othello.step(Duration::ZERO);
+        assert_eq!(othello.state(), GameState::Pass(Piece::BLACK));
+        assert_eq!(othello.moves(), &[Move::Pass(Piece::BLACK)]);
+
+        othello.computer_play(Engine::new(1));
+        othello.step(Duration::ZERO);
+        assert_eq!(othello.moves()[1], Move::Play(Piece::WHITE, GridPosition::new(3, 0)));
+        othello.step(Duration::ZERO);
+        assert_eq!(othello.state(), GameState::Finished(Outcome {
+            winner: Some(Piece::WHITE),
+            score: (0, 4),
+            reason: EndReason::NoMoreMoves,
+        }));
+    }
+
+    #[test]
+    fn resume_with_pass() {
+        let othello = game(Some("OXX------------- X"), "d1");
+        assert_eq!(othello.moves(), &[
+            Move::Pass(Piece::BLACK),
+            Move::Play(Piece::WHITE, GridPosition::new(3, 0)),
+        ]);
+        assert_eq!(othello.saved_game().moves, "d1");
+    }
+
+    #[test]
+    fn play() {
+        let mut othello = game(None, "");
+        let f5 = GridPosition::new(5, 4);
+        assert_eq!(othello.play(Piece::WHITE, f5),
+                   Err(OthelloError::WrongTurn { expected: Piece::BLACK, found: Piece::WHITE }));
+        assert_eq!(othello.play(Piece::BLACK, GridPosition::new(0, 0)),
+                   Err(OthelloError::IllegalMove { position: GridPosition::new(0, 0), piece: Piece::BLACK }));
+        let outcome = othello.play(Piece::BLACK, f5).unwrap();
+        assert_eq!(outcome.flips.positions, vec![GridPosition::new(4, 4)]);
+        assert_eq!(outcome.state, GameState::InProgress(Piece::WHITE));
+        assert_eq!(othello.moves(), &[Move::Play(Piece::BLACK, f5)]);
+
+        othello.resign();
+        assert_eq!(othello.play(Piece::WHITE, GridPosition::new(3, 5)), Err(OthelloError::GameOver));
+    }
+
+    #[test]
+    fn keyboard() {
+        let mut othello = game(None, "");
+        othello.move_cursor(-1, 0);
+        assert_eq!(othello.cursor, Some(GridPosition::new(3, 3)));
+        othello.move_cursor(-5, 10);
+        assert_eq!(othello.cursor, Some(GridPosition::new(0, 7)));
+
+        // The valid moves of black are d3, c4, f5 and e6
+        othello.cycle_valid_moves(true);
+        assert_eq!(othello.cursor, Some(GridPosition::new(3, 2)));
+        othello.cycle_valid_moves(false);
+        assert_eq!(othello.cursor, Some(GridPosition::new(4, 5)));
+        othello.cycle_valid_moves(false);
+        assert_eq!(othello.cursor, Some(GridPosition::new(5, 4)));
+
+        othello.play_cursor();
+        othello.step(Duration::ZERO);
+        assert_eq!(othello.moves(), &[Move::Play(Piece::BLACK, GridPosition::new(5, 4))]);
+
+        // An invalid cell is not played
+        othello.move_cursor(-8, -8);
+        othello.play_cursor();
+        othello.step(Duration::ZERO);
+        assert_eq!(othello.moves().len(), 1);
+    }
+
+    #[test]
+    fn sounds() {
+        let mut othello = game(None, "");
+        othello.choose(GridPosition::new(0, 0));
+        othello.play(Piece::BLACK, GridPosition::new(5, 4)).unwrap();
+        assert_eq!(othello.sounds, vec![Sound::Illegal, Sound::Place, Sound::Flip(1)]);
+        othello.sounds.clear();
+
+        othello.resign();
+        assert_eq!(othello.sounds, vec![Sound::GameEnd]);
+    }
+
+    #[test]
+    fn panel_text() {
+        let othello = game(None, "f5d6c3");
+        assert_eq!(othello.move_list(), vec![" 1. f5 d6", " 2. c3"]);
+        assert_eq!(othello.panel_text(10),
+            "  Albert Einstein (BLACK)\n  5 discs  00:00\n\n\
+             > Marie Curie (WHITE)\n  2 discs  00:00\n\n\
+             WHITE to play\n\nMoves:\n 1. f5 d6\n 2. c3");
+        assert!(othello.panel_text(1).ends_with("Moves:\n 2. c3"));
+
+        let othello = game(Some("OXX------------- X"), "d1");
+        assert_eq!(othello.move_list(), vec![" 1. pass d1"]);
+
+        let othello = game(Some("---------------------------OX------XO--------------------------- O"), "f4f5");
+        assert_eq!(othello.move_list(), vec![" 1. ... f4", " 2. f5"]);
+    }
+
+    #[test]
+    fn evaluations() {
+        // Black pass then white play d1 and win 4-0
+        let othello = game(Some("OXX------------- X"), "d1");
+        assert_eq!(othello.evaluations, vec![-4, -4]);
+        assert_eq!(othello.evaluation(), Some(-4));
+
+        let mut othello = game(None, "f5");
+        assert_eq!(othello.evaluations.len(), 2);
+        othello.play(Piece::WHITE, GridPosition::new(3, 5)).unwrap();
+        assert_eq!(othello.evaluations.len(), 3);
+        assert_eq!(othello.evaluation(), othello.evaluations.last().copied());
+        othello.reset();
+        assert_eq!(othello.evaluations.len(), 1);
+
+        // A board set without a saved game is evaluated too
+        let othello = Othello::new(PLAYER_ONE, PLAYER_TWO).set_board(Board::with_size((4, 4)));
+        assert_eq!(othello.evaluation(), Some(super::evaluate(&Board::with_size((4, 4)), Piece::BLACK)));
+
+        // Nothing is evaluated while the evaluation is hidden, every position is evaluated when it is shown
+        let mut othello = game(None, "f5d6").set_evaluation(false);
+        othello.play(Piece::BLACK, GridPosition::new(2, 2)).unwrap();
+        assert_eq!(othello.evaluation(), None);
+        othello.enable_evaluation(true);
+        assert_eq!(othello.evaluations.len(), 4);
     }
 }
@@ -1,14 +1,43 @@
 //! A module that describe players
 
+use std::borrow::Cow;
 use std::fmt;
 
 use crate::board::Piece;
+use crate::engine::Engine;
 
 /// Struct for manage player
-#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+#[derive(Clone, PartialEq, Eq, Debug)]
 pub struct Player {
-    pub name: &'static str,
+    pub name: Cow<'static, str>,
     pub piece: Piece,
+    pub kind: PlayerKind,
+}
+
+/// Describe who choose the moves of a player
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum PlayerKind {
+    /// Moves are given by a user (mouse click)
+    Human,
+    /// Moves are computed by an engine
+    Computer(Engine),
+}
+
+impl Player {
+    /// Create a human player
+    pub fn new<S: Into<Cow<'static, str>>>(name: S, piece: Piece) -> Self {
+        Player { name: name.into(), piece, kind: PlayerKind::Human }
+    }
+
+    /// Create a player played by the computer of a certain level
+    pub fn computer<S: Into<Cow<'static, str>>>(name: S, piece: Piece, level: u8) -> Self {
+        Player { name: name.into(), piece, kind: PlayerKind::Computer(Engine::from_level(level)) }
+    }
+
+    /// Verify if the moves of this player are chosen by a user
+    pub fn is_human(&self) -> bool {
+        self.kind == PlayerKind::Human
+    }
 }
 
 impl fmt::Display for Player {
@@ -24,8 +53,8 @@ mod tests {
 
     #[test]
     fn fmt_player() {
-        let player_one = Player { name: "Valentin Colin", piece: Piece::WHITE };
-        let player_two = Player { name: "Colin Valentin", piece: Piece::BLACK };
+        let player_one = Player::new("Valentin Colin", Piece::WHITE);
+        let player_two = Player::computer(String::from("Colin Valentin"), Piece::BLACK, 3);
 
         assert_eq!("Valentin Colin (WHITE)", format!("{}", player_one).as_str());
         assert_eq!("Colin Valentin (BLACK)", format!("{}", player_two).as_str());
@@ -0,0 +1,497 @@
+//! Puzzles: find the best move of the end of a game, the answer is verified by the exact solver
+//!
+//! A puzzle file has one position string per line (see [`Board::from_position_string`]),
+//! the empty lines and the lines starting with `#` are ignored:
+//!
+//! ```text
+//! # White to play and win
+//! OOOOOOOXOOXXXXXXOOOOOXXXOOXOXXXXOXOXXOXXOOXXOXOXOXOOX--XX-OOOO-- O
+//! ```
+//!
+//! Puzzles are generated from a file of games (one transcript per line) with
+//! `--generate-puzzles`: a position of a game is a puzzle when exactly one move win.
+
+use std::error::Error;
+use std::fmt;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+use ggez::{Context, GameResult};
+use ggez::event::{KeyCode, KeyMods, MouseButton};
+use ggez::graphics::{self, Color};
+use log::{debug, error, info};
+use serde::{Deserialize, Serialize};
+
+use crate::*;
+use crate::analysis::BEST_MOVE_COLOR;
+
+/// The puzzles of the main menu
+pub const BUILTIN_PUZZLES: &str = include_str!("../resources/puzzles/endgames.txt");
+
+/// Name of the file of the puzzle statistics in the data directory
+pub const PUZZLE_STATS_FILE_NAME: &str = "puzzles.toml";
+
+/// Most empty cases of a generated puzzle when it is not chosen, the solver is slow above
+pub const DEFAULT_PUZZLE_EMPTY_CASES: usize = 12;
+
+/// A position where the side to move have to find the best move
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Puzzle {
+    pub board: Board,
+    /// Who have to play
+    pub turn: Piece,
+}
+
+/// The answer to a puzzle, verified by the exact solver
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Answer {
+    pub played: GridPosition,
+    /// Final disc difference after the played move for the side to move (with a perfect play)
+    pub difference: i32,
+    /// The best move and its final disc difference
+    pub best: GridPosition,
+    pub best_difference: i32,
+}
+
+/// Success of the player in the puzzles, saved in the data directory
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct PuzzleStats {
+    pub attempts: u32,
+    pub solved: u32,
+    /// Number of puzzles solved in a row
+    pub streak: u32,
+    pub best_streak: u32,
+}
+
+/// Error raised while loading puzzles
+#[derive(Debug)]
+pub enum PuzzleError {
+    /// The file cannot be read
+    Io(PathBuf, io::Error),
+    /// A line is not a valid position (the line number start at 1)
+    Invalid(usize, NotationError),
+    /// There is no puzzle in the file
+    Empty,
+}
+
+impl Puzzle {
+    /// Read a puzzle from a position string, the board must be a square
+    pub fn parse(position: &str) -> Result<Self, NotationError> {
+        let (board, turn) = Board::from_position_string(position, None)?;
+        Ok(Puzzle { board, turn })
+    }
+
+    /// Read a list of puzzles, one position string per line
+    pub fn parse_list(content: &str) -> Result<Vec<Puzzle>, PuzzleError> {
+        let puzzles = content.lines()
+            .enumerate()
+            .filter(|(_, line)| !line.trim().is_empty() && !line.trim_start().starts_with('#'))
+            .map(|(index, line)| Puzzle::parse(line).map_err(|err| PuzzleError::Invalid(index + 1, err)))
+            .collect::<Result<Vec<Puzzle>, PuzzleError>>()?;
+        if puzzles.is_empty() {
+            return Err(PuzzleError::Empty);
+        }
+        Ok(puzzles)
+    }
+
+    /// Load a file of puzzles
+    pub fn load(path: &Path) -> Result<Vec<Puzzle>, PuzzleError> {
+        let content = fs::read_to_string(path).map_err(|err| PuzzleError::Io(path.to_path_buf(), err))?;
+        Puzzle::parse_list(&content)
+    }
+
+    /// Return the puzzles of the main menu
+    pub fn builtin() -> Vec<Puzzle> {
+        Puzzle::parse_list(BUILTIN_PUZZLES).expect("The built-in puzzles are valid")
+    }
+
+    /// Write the puzzle as a position string
+    pub fn to_position_string(&self) -> String {
+        self.board.to_position_string(self.turn)
+    }
+
+    /// Return the number of empty cases
+    pub fn empty_cases(&self) -> usize {
+        empty_cases(&self.board)
+    }
+
+    /// Verify a move with the exact solver, `None` if the move is not valid
+    pub fn check(&self, position: GridPosition) -> Option<Answer> {
+        let solution = Engine::solve(&self.board, self.turn);
+        let difference = solution.iter().find(|(other, _)| *other == position)?.1;
+        let (best, best_difference) = solution.into_iter().fold((position, difference), |best, other| {
+            if other.1 > best.1 { other } else { best }
+        });
+        Some(Answer { played: position, difference, best, best_difference })
+    }
+
+    /// Find the puzzles of a game: the positions with at most `max_empty_cases` empty cases
+    /// where exactly one move win
+    pub fn generate(game: &SavedGame, max_empty_cases: usize) -> Result<Vec<Puzzle>, NotationError> {
+        let (mut board, mut turn) = game.start()?;
+        let mut puzzles = Vec::new();
+        for (index, position) in game.moves()?.into_iter().enumerate() {
+            let piece = if board.can_play(turn) { turn } else { turn.next() };
+            if empty_cases(&board) <= max_empty_cases && board.get_valid_moves(piece).len() > 1 {
+                let winning_moves = Engine::solve(&board, piece).iter().filter(|(_, difference)| *difference > 0).count();
+                if winning_moves == 1 {
+                    puzzles.push(Puzzle { board: board.clone(), turn: piece });
+                }
+            }
+            turn = play_transcript_move(&mut board, turn, index, position)?.next();
+        }
+        Ok(puzzles)
+    }
+}
+
+/// Return the number of empty cases of a board
+pub(crate) fn empty_cases(board: &Board) -> usize {
+    let (width, height) = board.size();
+    (width * height) as usize - board.score(Piece::BLACK) as usize - board.score(Piece::WHITE) as usize
+}
+
+/// Describe a final disc difference, e.g. "win by 4"
+fn difference_text(difference: i32) -> String {
+    match difference {
+        0 => String::from("draw"),
+        difference if difference > 0 => format!("win by {}", difference),
+        difference => format!("lose by {}", -difference),
+    }
+}
+
+impl Answer {
+    /// Verify if the played move is as good as the best move
+    pub fn is_correct(&self) -> bool {
+        self.difference == self.best_difference
+    }
+
+    /// Describe the answer, e.g. "Right! c3 win by 4"
+    pub fn text(&self) -> String {
+        if self.is_correct() {
+            format!("Right! {} {}", self.played.to_notation(), difference_text(self.difference))
+        } else {
+            format!("Wrong: {} {}\nThe best move is {} ({})",
+                self.played.to_notation(), difference_text(self.difference),
+                self.best.to_notation(), difference_text(self.best_difference))
+        }
+    }
+}
+
+impl PuzzleStats {
+    /// Count an answer
+    pub fn record(&mut self, correct: bool) {
+        self.attempts += 1;
+        if correct {
+            self.solved += 1;
+            self.streak += 1;
+            self.best_streak = self.best_streak.max(self.streak);
+        } else {
+            self.streak = 0;
+        }
+    }
+
+    /// Return the part of the puzzles solved (from 0 to 1), 0 before the first answer
+    pub fn success_rate(&self) -> f32 {
+        if self.attempts == 0 { 0.0 } else { self.solved as f32 / self.attempts as f32 }
+    }
+
+    /// Load the statistics of the data directory, new ones if there is no file
+    pub fn load() -> Self {
+        let path = match SavedGame::data_path(PUZZLE_STATS_FILE_NAME) {
+            Some(path) if path.exists() => path,
+            _ => return PuzzleStats::default(),
+        };
+        fs::read_to_string(&path)
+            .map_err(|err| err.to_string())
+            .and_then(|content| toml::from_str(&content).map_err(|err| err.to_string()))
+            .unwrap_or_else(|err| {
+                error!("Cannot load the puzzle statistics {}: {}", path.display(), err);
+                PuzzleStats::default()
+            })
+    }
+
+    /// Save the statistics in the data directory
+    pub fn save(&self) {
+        let path = match SavedGame::data_path(PUZZLE_STATS_FILE_NAME) {
+            Some(path) => path,
+            None => return error!("No directory to save the puzzle statistics"),
+        };
+        let result = toml::to_string(self)
+            .map_err(|err| err.to_string())
+            .and_then(|content| {
+                if let Some(directory) = path.parent() {
+                    fs::create_dir_all(directory).map_err(|err| err.to_string())?;
+                }
+                fs::write(&path, content).map_err(|err| err.to_string())
+            });
+        match result {
+            Ok(()) => info!("The puzzle statistics are saved in {}", path.display()),
+            Err(err) => error!("Cannot save the puzzle statistics in {}: {}", path.display(), err),
+        }
+    }
+}
+
+impl fmt::Display for PuzzleStats {
+    /// Write the statistics, e.g. "Solved 3/4 (75%)\nStreak 2, best 3"
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "Solved {}/{} ({:.0}%)\nStreak {}, best {}",
+            self.solved, self.attempts, 100.0 * self.success_rate(), self.streak, self.best_streak)
+    }
+}
+
+impl fmt::Display for PuzzleError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            PuzzleError::Io(path, err) => write!(f, "cannot read {}: {}", path.display(), err),
+            PuzzleError::Invalid(line, err) => write!(f, "invalid puzzle at line {}: {}", line, err),
+            PuzzleError::Empty => write!(f, "there is no puzzle"),
+        }
+    }
+}
+
+impl Error for PuzzleError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            PuzzleError::Io(_, err) => Some(err),
+            PuzzleError::Invalid(_, err) => Some(err),
+            PuzzleError::Empty => None,
+        }
+    }
+}
+
+/// The screen of the puzzles: the board of a puzzle and the statistics
+#[derive(Debug, Clone)]
+pub struct PuzzleScene {
+    puzzles: Vec<Puzzle>,
+    /// The shown puzzle
+    index: usize,
+    /// The answer to the shown puzzle, `None` until the player play
+    answer: Option<Answer>,
+    /// The cell chosen with the keyboard
+    cursor: Option<GridPosition>,
+    stats: PuzzleStats,
+    theme: Theme,
+}
+
+impl PuzzleScene {
+    /// Show a list of puzzles (at least one) from the first, with the saved statistics
+    pub fn new(puzzles: Vec<Puzzle>, theme: Theme) -> Self {
+        PuzzleScene { puzzles, index: 0, answer: None, cursor: None, stats: PuzzleStats::load(), theme }
+    }
+
+    /// Return the shown puzzle
+    fn puzzle(&self) -> &Puzzle {
+        &self.puzzles[self.index]
+    }
+
+    /// Answer the shown puzzle, `false` if the move is not valid
+    fn answer(&mut self, position: GridPosition) -> bool {
+        if self.answer.is_some() {
+            return true;
+        }
+        match self.puzzle().check(position) {
+            Some(answer) => {
+                info!("PUZZLE {}: {}", self.index + 1, answer.text());
+                self.stats.record(answer.is_correct());
+                self.answer = Some(answer);
+                true
+            }
+            None => {
+                debug!("The position {} is not valid in the puzzle", position);
+                false
+            }
+        }
+    }
+
+    /// Show the next puzzle, the first one after the last one
+    fn next(&mut self) {
+        self.index = (self.index + 1) % self.puzzles.len();
+        self.answer = None;
+        self.cursor = None;
+    }
+
+    /// Move the keyboard cursor, it appears in the middle of the board the first time
+    fn move_cursor(&mut self, dx: i16, dy: i16) {
+        let (width, height) = self.puzzle().board.size();
+        self.cursor = Some(match self.cursor {
+            Some(cursor) => GridPosition::new((cursor.x + dx).clamp(0, width - 1), (cursor.y + dy).clamp(0, height - 1)),
+            None => GridPosition::new(width / 2 - 1, height / 2 - 1),
+        });
+    }
+
+    /// Return the text of the panel
+    fn panel_text(&self) -> String {
+        let puzzle = self.puzzle();
+        let mut text = format!("Puzzle {}/{}\n\n{} to play\nFind the best move\n\n",
+            self.index + 1, self.puzzles.len(), puzzle.turn);
+        match &self.answer {
+            Some(answer) => text += &format!("{}\n\nEnter: next puzzle", answer.text()),
+            None => text += "N: skip the puzzle",
+        }
+        text += &format!("\n\n{}", self.stats);
+        text
+    }
+
+    /// Play a move (a wrong move play a sound), go to the next puzzle if it is answered
+    fn choose(&mut self, ctx: &mut Context, world: &mut World, position: GridPosition) {
+        if self.answer.is_some() {
+            self.next();
+        } else if !self.answer(position) {
+            world.play_sound(ctx, Sound::Illegal);
+        } else if self.answer.is_some_and(|answer| answer.is_correct()) {
+            world.play_sound(ctx, Sound::GameEnd);
+        } else {
+            world.play_sound(ctx, Sound::Pass);
+        }
+    }
+}
+
+impl Scene for PuzzleScene {
+    /// Draw the puzzle (the valid moves until the answer, then the played and the best moves) and the panel
+    fn draw(&mut self, ctx: &mut Context, _world: &World) -> GameResult {
+        graphics::clear(ctx, self.theme.background_color);
+        let layout = Layout::from_context(ctx);
+        let puzzle = self.puzzle();
+        let theme = match self.answer {
+            Some(_) => Theme { valid_moves_color: None, ..self.theme.clone() },
+            None => self.theme.clone(),
+        };
+        puzzle.board.draw_animated(ctx, layout.board, puzzle.turn, &theme, None)?;
+        if let Some(answer) = self.answer {
+            let best_theme = Theme { cursor_color: BEST_MOVE_COLOR, ..theme.clone() };
+            puzzle.board.draw_cursor(ctx, &best_theme, answer.best, layout.board)?;
+            puzzle.board.draw_cursor(ctx, &theme, answer.played, layout.board)?;
+        } else if let Some(cursor) = self.cursor {
+            puzzle.board.draw_cursor(ctx, &theme, cursor, layout.board)?;
+        }
+
+        let area = layout.panel;
+        let margin = 10.0 * layout.scale;
+        let background = graphics::MeshBuilder::new()
+            .rectangle(graphics::DrawMode::fill(), area, Color::new(0.0, 0.0, 0.0, 0.6))?
+            .build(ctx)?;
+        graphics::draw(ctx, &background, graphics::DrawParam::default())?;
+        let font = graphics::Font::new(ctx, self.theme.font_path.as_ref())?;
+        let mut text = graphics::Text::new((self.panel_text(), font, layout.font_size(self.theme.font_scale)));
+        text.set_bounds(glam::Vec2::new(area.w - 2.0 * margin, area.h - 2.0 * margin), graphics::Align::Left);
+        graphics::draw(ctx, &text, (glam::Vec2::new(area.x + margin, area.y + margin), Color::WHITE))
+    }
+
+    /// Play the clicked case, or go to the next puzzle when the puzzle is answered
+    fn mouse_down(&mut self, ctx: &mut Context, world: &mut World, button: MouseButton, x: f32, y: f32) -> Transition {
+        if button != MouseButton::Left {
+            return Transition::None;
+        }
+        let mut layout = Layout::from_context(ctx);
+        layout.board = self.theme.grid_area(layout.board);
+        let board = &self.puzzle().board;
+        let cell_size = board.cell_size((layout.board.w, layout.board.h));
+        if let Some(position) = layout.board_position(x, y).map(|position| position.into_grid(cell_size)) {
+            self.choose(ctx, world, position);
+        }
+        Transition::None
+    }
+
+    /// Called every time a key gets pressed
+    ///
+    /// - Arrows: move the cursor
+    /// - Enter / Space: play at the cursor, or next puzzle when the puzzle is answered
+    /// - N: next puzzle
+    /// - Escape: back to the menu
+    fn key_down(&mut self, ctx: &mut Context, world: &mut World, keycode: KeyCode, _keymod: KeyMods) -> Transition {
+        match keycode {
+            KeyCode::Escape => {info!("LEAVE the puzzles from key Escape"); return Transition::Pop;},
+            KeyCode::Up => self.move_cursor(0, -1),
+            KeyCode::Down => self.move_cursor(0, 1),
+            KeyCode::Left => self.move_cursor(-1, 0),
+            KeyCode::Right => self.move_cursor(1, 0),
+            KeyCode::N => self.next(),
+            KeyCode::Return | KeyCode::NumpadEnter | KeyCode::Space => match self.cursor {
+                Some(cursor) => self.choose(ctx, world, cursor),
+                None if self.answer.is_some() => self.next(),
+                None => self.move_cursor(0, 0),
+            },
+            _ => {}
+        }
+        Transition::None
+    }
+
+    /// Save the statistics when the puzzles are left
+    fn leave(&mut self, _world: &mut World) {
+        self.stats.save();
+    }
+}
+
+#[allow(unreachable_code)]
+#[cfg(test)]
+mod tests {
+    use crate::*;
+
+    /// Black have two moves, a1 (9-7) and d4 (13-3), see the test of `Engine::solve`
+    const POSITION: &str = "-OXX OXXX OXOX OXX- X";
+
+    #[test]
+    fn check() {
+        let puzzle = Puzzle::parse(POSITION).unwrap();
+        assert_eq!(puzzle.empty_cases(), 2);
+        assert_eq!(puzzle.check(GridPosition::new(1, 0)), None);
+
+        let answer = puzzle.check(GridPosition::new(0, 0)).unwrap();
+        assert!(!answer.is_correct());
+        assert_eq!(answer.best, GridPosition::new(3, 3));
+        assert_eq!(answer.text(), "Wrong: a1 win by 2\nThe best move is d4 (win by 10)");
+        let answer = puzzle.check(GridPosition::new(3, 3)).unwrap();
+        assert!(answer.is_correct());
+        assert_eq!(answer.text(), "Right! d4 win by 10");
+    }
+
+    #[test]
+    fn parse_list() {
+        let puzzles = Puzzle::parse_list(&format!("# A comment\n\n{}\n", POSITION)).unwrap();
+        assert_eq!(puzzles.len(), 1);
+        assert_eq!(puzzles[0].to_position_string(), "-OXXOXXXOXOXOXX- X");
+        assert!(matches!(Puzzle::parse_list("# Nothing\n"), Err(PuzzleError::Empty)));
+        assert!(matches!(Puzzle::parse_list("XO\n"), Err(PuzzleError::Invalid(1, _))));
+    }
+
+    #[test]
+    fn builtin() {
+        let puzzles = Puzzle::builtin();
+        assert!(!puzzles.is_empty());
+        assert!(puzzles.iter().all(|puzzle| puzzle.board.can_play(puzzle.turn)));
+        // Each built-in puzzle has exactly one winning move
+        for puzzle in puzzles {
+            let winning_moves = Engine::solve(&puzzle.board, puzzle.turn).iter().filter(|(_, difference)| *difference > 0).count();
+            assert_eq!(winning_moves, 1, "{}", puzzle.to_position_string());
+        }
+    }
+
+    #[test]
+    fn generate() {
+        // The puzzle is the last position of the game, before d4
+        let game = SavedGame {
+            position: Some(String::from(POSITION)),
+            moves: String::from("d4a1"),
+            board: BoardConfig { width: 4, height: 4 },
+            ..SavedGame::default()
+        };
+        assert_eq!(Puzzle::generate(&game, 2).unwrap(), vec![]);
+        let game = SavedGame { position: Some(String::from("-OXX OXXX OXOX OXX- O")), moves: String::from("d4"), ..game };
+        // White: a1 win by 4 and d4 lose by 2
+        assert_eq!(Puzzle::generate(&game, 2).unwrap(), vec![Puzzle::parse("-OXXOXXXOXOXOXX- O").unwrap()]);
+        assert_eq!(Puzzle::generate(&game, 1).unwrap(), vec![]);
+    }
+
+    #[test]
+    fn stats() {
+        let mut stats = PuzzleStats::default();
+        assert_eq!(stats.success_rate(), 0.0);
+        stats.record(true);
+        stats.record(true);
+        stats.record(false);
+        stats.record(true);
+        assert_eq!(stats.to_string(), "Solved 3/4 (75%)\nStreak 1, best 2");
+    }
+}
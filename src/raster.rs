@@ -0,0 +1,174 @@
+//! A small software renderer to draw images without window (e.g. the frames of a GIF)
+
+use std::fs;
+use std::io;
+use std::path::PathBuf;
+
+use ab_glyph::{point, Font, FontVec, PxScale, ScaleFont};
+use ggez::graphics::{Color, Rect};
+use glam::Vec2;
+
+/// An image in memory, 4 bytes (RGBA) per pixel row by row
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Raster {
+    width: usize,
+    height: usize,
+    pixels: Vec<u8>,
+}
+
+impl Raster {
+    /// Create an image filled with a color
+    pub fn new(width: usize, height: usize, background: Color) -> Self {
+        let (r, g, b, _) = background.to_rgba();
+        Raster { width, height, pixels: [r, g, b, u8::MAX].repeat(width * height) }
+    }
+
+    /// Return the size of the image (in pixel)
+    pub fn size(&self) -> (usize, usize) {
+        (self.width, self.height)
+    }
+
+    /// Return the pixels of the image, 4 bytes (RGBA) per pixel
+    pub fn pixels(&self) -> &[u8] {
+        &self.pixels
+    }
+
+    /// Return the pixels of the image to modify them
+    pub fn pixels_mut(&mut self) -> &mut [u8] {
+        &mut self.pixels
+    }
+
+    /// Return the color of a pixel, `None` outside of the image
+    pub fn get(&self, x: usize, y: usize) -> Option<Color> {
+        if x >= self.width || y >= self.height {
+            return None;
+        }
+        let index = 4 * (y * self.width + x);
+        let pixel = &self.pixels[index..index + 4];
+        Some(Color::from_rgba(pixel[0], pixel[1], pixel[2], pixel[3]))
+    }
+
+    /// Blend a color on a pixel, `coverage` is the part of the pixel covered (from 0 to 1)
+    fn blend(&mut self, x: i64, y: i64, color: Color, coverage: f32) {
+        if x < 0 || y < 0 || x as usize >= self.width || y as usize >= self.height {
+            return;
+        }
+        let alpha = (color.a * coverage).clamp(0.0, 1.0);
+        if alpha <= 0.0 {
+            return;
+        }
+        let index = 4 * (y as usize * self.width + x as usize);
+        for (channel, value) in [color.r, color.g, color.b].into_iter().enumerate() {
+            let old = self.pixels[index + channel] as f32 / 255.0;
+            self.pixels[index + channel] = ((value * alpha + old * (1.0 - alpha)) * 255.0).round() as u8;
+        }
+    }
+
+    /// Fill a rectangle, the borders are smoothed when they are not on the pixels
+    pub fn fill_rect(&mut self, rect: Rect, color: Color) {
+        let (left, top) = (rect.left().floor() as i64, rect.top().floor() as i64);
+        let (right, bottom) = (rect.right().ceil() as i64, rect.bottom().ceil() as i64);
+        for y in top..bottom {
+            let covered_y = (rect.bottom().min(y as f32 + 1.0) - rect.top().max(y as f32)).max(0.0);
+            for x in left..right {
+                let covered_x = (rect.right().min(x as f32 + 1.0) - rect.left().max(x as f32)).max(0.0);
+                self.blend(x, y, color, covered_x * covered_y);
+            }
+        }
+    }
+
+    /// Draw a ring between the radius `inner` and `outer`, the edges are smoothed
+    fn fill_ring(&mut self, center: Vec2, inner: f32, outer: f32, color: Color) {
+        let (left, top) = ((center.x - outer).floor() as i64, (center.y - outer).floor() as i64);
+        let (right, bottom) = ((center.x + outer).ceil() as i64, (center.y + outer).ceil() as i64);
+        for y in top..bottom {
+            for x in left..right {
+                let distance = Vec2::new(x as f32 + 0.5, y as f32 + 0.5).distance(center);
+                let coverage = (outer + 0.5 - distance).min(distance - inner + 0.5).clamp(0.0, 1.0);
+                self.blend(x, y, color, coverage);
+            }
+        }
+    }
+
+    /// Fill a circle
+    pub fn fill_circle(&mut self, center: Vec2, radius: f32, color: Color) {
+        self.fill_ring(center, f32::NEG_INFINITY, radius, color);
+    }
+
+    /// Draw the outline of a circle, the line of width `width` is centred on the circle
+    pub fn stroke_circle(&mut self, center: Vec2, radius: f32, width: f32, color: Color) {
+        self.fill_ring(center, radius - width / 2.0, radius + width / 2.0, color);
+    }
+
+    /// Write a text on one line centred on a point, `size` is the height of the font (in pixel)
+    pub fn text(&mut self, font: &FontVec, text: &str, center: Vec2, size: f32, color: Color) {
+        let scaled = font.as_scaled(PxScale::from(size));
+        let mut x = center.x - text_width(font, text, size) / 2.0;
+        let baseline = center.y + (scaled.ascent() + scaled.descent()) / 2.0;
+        for c in text.chars() {
+            let id = scaled.glyph_id(c);
+            let glyph = id.with_scale_and_position(size, point(x, baseline));
+            x += scaled.h_advance(id);
+            if let Some(outline) = font.outline_glyph(glyph) {
+                let bounds = outline.px_bounds();
+                outline.draw(|gx, gy, coverage| {
+                    self.blend(bounds.min.x as i64 + gx as i64, bounds.min.y as i64 + gy as i64, color, coverage);
+                });
+            }
+        }
+    }
+}
+
+/// Return the width (in pixel) of a text written on one line
+pub fn text_width(font: &FontVec, text: &str, size: f32) -> f32 {
+    let scaled = font.as_scaled(PxScale::from(size));
+    text.chars().map(|c| scaled.h_advance(scaled.glyph_id(c))).sum()
+}
+
+/// Load a font of resources/, like the `font_path` of a [`crate::Theme`] ("/fonts/...")
+pub fn load_font(path: &str) -> io::Result<FontVec> {
+    let file: PathBuf = [env!("CARGO_MANIFEST_DIR"), "resources", path.trim_start_matches('/')].iter().collect();
+    let data = fs::read(&file)?;
+    FontVec::try_from_vec(data).map_err(|err| io::Error::new(io::ErrorKind::InvalidData, err))
+}
+
+#[allow(unreachable_code)]
+#[cfg(test)]
+mod tests {
+    use ggez::graphics::{Color, Rect};
+    use glam::Vec2;
+
+    use crate::*;
+
+    #[test]
+    fn shapes() {
+        let mut raster = Raster::new(20, 10, Color::BLACK);
+        assert_eq!(raster.size(), (20, 10));
+        assert_eq!(raster.pixels().len(), 20 * 10 * 4);
+        raster.fill_rect(Rect::new(0.0, 0.0, 5.0, 10.0), Color::WHITE);
+        raster.fill_circle(Vec2::new(15.0, 5.0), 4.0, Color::RED);
+
+        assert_eq!(raster.get(2, 2), Some(Color::WHITE));
+        assert_eq!(raster.get(5, 2), Some(Color::BLACK));
+        assert_eq!(raster.get(15, 5), Some(Color::RED));
+        assert_eq!(raster.get(19, 0), Some(Color::BLACK));
+        assert_eq!(raster.get(20, 0), None);
+        // Partly covered by the circle
+        let edge = raster.get(18, 2).unwrap();
+        assert!(edge.r > 0.1 && edge.r < 0.9);
+    }
+
+    #[test]
+    fn text() {
+        let font = load_font(DEFAULT_THEME.font_path.as_ref()).unwrap();
+        assert_eq!(text_width(&font, "", 20.0), 0.0);
+        // A monospace font
+        assert_eq!(text_width(&font, "ab", 20.0), 2.0 * text_width(&font, "i", 20.0));
+
+        let mut raster = Raster::new(40, 20, Color::BLACK);
+        raster.text(&font, "8", Vec2::new(20.0, 10.0), 16.0, Color::WHITE);
+        let lit = raster.pixels().chunks(4).filter(|pixel| pixel[0] > 128).count();
+        assert!(lit > 10);
+        assert_eq!(raster.get(2, 10), Some(Color::BLACK));
+    }
+}
@@ -0,0 +1,262 @@
+//! Replay a recorded game move by move
+
+use std::time::Duration;
+
+use ggez::{Context, GameResult, timer};
+use ggez::event::{KeyCode, KeyMods};
+use ggez::graphics::{self, Color, Rect};
+use log::{debug, info};
+
+use crate::*;
+
+/// Speed of the auto-play when it is not chosen (moves per second)
+pub const DEFAULT_REPLAY_SPEED: f32 = 1.0;
+
+/// Slowest and fastest speed of the auto-play (moves per second)
+const REPLAY_SPEED_RANGE: (f32, f32) = (0.25, 16.0);
+
+/// State of the replay viewer
+pub struct Replay {
+    player_black: Player,
+    player_white: Player,
+    /// The board before the first move then after each move
+    boards: Vec<Board>,
+    /// The played moves and who played them
+    moves: Vec<(GridPosition, Piece)>,
+    /// Number of moves played on the shown board
+    index: usize,
+    autoplay: bool,
+    /// Speed of the auto-play (moves per second)
+    speed: f32,
+    /// Time since the last move of the auto-play
+    elapsed: Duration,
+    theme: Theme,
+}
+
+impl Replay {
+    /// Create the replay of a saved game, the start position is shown
+    pub fn new(game: &SavedGame) -> Result<Self, NotationError> {
+        let (mut board, mut turn) = game.start()?;
+        let mut boards = vec![board.clone()];
+        let mut moves = Vec::new();
+        for (index, position) in game.moves()?.into_iter().enumerate() {
+            let piece = play_transcript_move(&mut board, turn, index, position)?;
+            boards.push(board.clone());
+            moves.push((position, piece));
+            turn = piece.next();
+        }
+
+        let (player_black, player_white) = game.players();
+        let theme = game.theme.as_ref().map(ThemeConfig::theme).unwrap_or_default();
+        Ok(Replay {
+            player_black,
+            player_white,
+            boards,
+            moves,
+            index: 0,
+            autoplay: false,
+            speed: DEFAULT_REPLAY_SPEED,
+            elapsed: Duration::ZERO,
+            theme,
+        })
+    }
+
+    /// Set the theme of the replay
+    pub fn set_theme(mut self, theme: Theme) -> Self {
+        self.theme = theme;
+        self
+    }
+
+    /// Set the speed of the auto-play (moves per second) and start it
+    ///
+    /// A speed that is not a number is replaced by [`DEFAULT_REPLAY_SPEED`].
+    pub fn autoplay(mut self, speed: f32) -> Self {
+        let speed = if speed.is_nan() { DEFAULT_REPLAY_SPEED } else { speed };
+        self.speed = speed.clamp(REPLAY_SPEED_RANGE.0, REPLAY_SPEED_RANGE.1);
+        self.autoplay = true;
+        self
+    }
+
+    /// Return the number of moves played on the shown board
+    pub fn index(&self) -> usize {
+        self.index
+    }
+
+    /// Return the shown board
+    pub fn board(&self) -> &Board {
+        &self.boards[self.index]
+    }
+
+    /// Show the board after the next move
+    pub fn forward(&mut self) {
+        self.index = (self.index + 1).min(self.moves.len());
+    }
+
+    /// Show the board before the last move
+    pub fn backward(&mut self) {
+        self.index = self.index.saturating_sub(1);
+    }
+
+    /// Show the start position
+    pub fn to_start(&mut self) {
+        self.index = 0;
+    }
+
+    /// Show the end of the game
+    pub fn to_end(&mut self) {
+        self.index = self.moves.len();
+    }
+
+    /// Change the speed of the auto-play
+    fn change_speed(&mut self, factor: f32) {
+        self.speed = (self.speed * factor).clamp(REPLAY_SPEED_RANGE.0, REPLAY_SPEED_RANGE.1);
+        debug!("Replay speed: {} moves per second", self.speed);
+    }
+
+    /// Describe the shown board, e.g. "Move 3/60: Albert Einstein (BLACK) played c3"
+    pub fn caption(&self) -> String {
+        let caption = match self.index {
+            0 => format!("Start position (0/{})", self.moves.len()),
+            index => {
+                let (position, piece) = self.moves[index - 1];
+                let player = if piece == Piece::WHITE { &self.player_white } else { &self.player_black };
+                format!("Move {}/{}: {} played {}", index, self.moves.len(), player, position.to_notation())
+            }
+        };
+        if self.autoplay {
+            format!("{}  [auto-play x{}]", caption, self.speed)
+        } else {
+            caption
+        }
+    }
+
+    /// Draw the caption on the top of the screen
+    fn draw_caption(&self, ctx: &mut Context, layout: &Layout) -> GameResult {
+        let margin = 10.0 * layout.scale;
+        let font = graphics::Font::new(ctx, self.theme.font_path.as_ref())?;
+        let text = graphics::Text::new((self.caption(), font, layout.font_size(self.theme.font_scale)));
+        let dimensions = text.dimensions(ctx);
+
+        let background = graphics::MeshBuilder::new()
+            .rectangle(
+                graphics::DrawMode::fill(),
+                Rect::new(0.0, 0.0, dimensions.w + 2.0 * margin, dimensions.h + margin),
+                Color::new(0.0, 0.0, 0.0, 0.6),
+            )?
+            .build(ctx)?;
+        graphics::draw(ctx, &background, graphics::DrawParam::default())?;
+        graphics::draw(ctx, &text, (glam::Vec2::new(margin, margin / 2.0), Color::WHITE))?;
+        Ok(())
+    }
+}
+
+impl Scene for Replay {
+    /// Play the next move when the auto-play is on
+    fn update(&mut self, ctx: &mut Context, _world: &mut World) -> GameResult<Transition> {
+        if self.autoplay {
+            self.elapsed += timer::delta(ctx);
+            if self.elapsed.as_secs_f32() >= 1.0 / self.speed {
+                self.elapsed = Duration::ZERO;
+                self.forward();
+                if self.index == self.moves.len() {
+                    self.autoplay = false;
+                }
+            }
+        }
+        Ok(Transition::None)
+    }
+
+    /// Render the shown board and the caption
+    fn draw(&mut self, ctx: &mut Context, _world: &World) -> GameResult {
+        graphics::clear(ctx, self.theme.background_color);
+
+        // The valid moves are not useful during a replay
+        let theme = Theme { valid_moves_color: None, ..self.theme.clone() };
+        let layout = Layout::from_context(ctx);
+        self.board().draw_animated(ctx, layout.board, Piece::EMPTY, &theme, None)?;
+        self.draw_caption(ctx, &layout)
+    }
+
+    /// Called every time a key gets pressed
+    ///
+    /// - Left / Right: previous / next move
+    /// - Home / End: start / end of the game
+    /// - Space: start / stop the auto-play
+    /// - + / -: faster / slower auto-play
+    /// - T: next theme
+    /// - Escape: back to the previous screen
+    fn key_down(&mut self, _ctx: &mut Context, world: &mut World, keycode: KeyCode, _keymod: KeyMods) -> Transition {
+        match keycode {
+            KeyCode::Escape => {info!("LEAVE the replay from key Escape"); return Transition::Pop;},
+            KeyCode::Right => self.forward(),
+            KeyCode::Left => self.backward(),
+            KeyCode::Home => self.to_start(),
+            KeyCode::End => self.to_end(),
+            KeyCode::Space => {
+                self.autoplay = !self.autoplay;
+                self.elapsed = Duration::ZERO;
+                if self.autoplay && self.index == self.moves.len() {
+                    self.to_start();
+                }
+            },
+            KeyCode::Plus | KeyCode::Equals | KeyCode::NumpadAdd => self.change_speed(2.0),
+            KeyCode::Minus | KeyCode::NumpadSubtract => self.change_speed(0.5),
+            KeyCode::T => self.theme = world.next_theme().clone(),
+            _ => {}
+        };
+        Transition::None
+    }
+}
+
+#[allow(unreachable_code)]
+#[cfg(test)]
+mod tests {
+    use crate::*;
+
+    fn replay(moves: &str) -> Replay {
+        let game = SavedGame { moves: String::from(moves), ..SavedGame::default() };
+        Replay::new(&game).unwrap()
+    }
+
+    #[test]
+    fn navigate() {
+        let mut replay = replay("f5d6c3");
+        assert_eq!(replay.board(), &Board::default());
+
+        replay.backward();
+        assert_eq!(replay.index(), 0);
+        replay.forward();
+        replay.forward();
+        assert_eq!(replay.index(), 2);
+        assert_eq!(replay.board().score(Piece::WHITE), 3);
+
+        replay.to_end();
+        assert_eq!(replay.index(), 3);
+        replay.forward();
+        assert_eq!(replay.index(), 3);
+        replay.backward();
+        assert_eq!(replay.index(), 2);
+        replay.to_start();
+        assert_eq!(replay.board(), &Board::default());
+    }
+
+    #[test]
+    fn caption() {
+        let mut replay = replay("f5d6");
+        assert_eq!(replay.caption(), "Start position (0/2)");
+        replay.to_end();
+        assert_eq!(replay.caption(), "Move 2/2: Marie Curie (WHITE) played d6");
+    }
+
+    #[test]
+    fn autoplay_speed() {
+        assert_eq!(replay("f5").autoplay(f32::NAN).speed, super::DEFAULT_REPLAY_SPEED);
+        assert_eq!(replay("f5").autoplay(1000.0).speed, 16.0);
+    }
+
+    #[test]
+    fn illegal_move() {
+        let game = SavedGame { moves: String::from("f5a1"), ..SavedGame::default() };
+        assert!(Replay::new(&game).is_err());
+    }
+}
@@ -0,0 +1,270 @@
+//! Games saved in a TOML file
+//!
+//! ```toml
+//! moves = "f5d6c3d3c4"
+//! # position = "...X"   # start position, the standard start if missing
+//! # end = "black-resigned"  # "white-resigned", "black-timeout" or "white-timeout"
+//!
+//! [clocks]               # time used by each player (in seconds)
+//! black = 12.5
+//! white = 20.0
+//!
+//! [black]
+//! name = "Albert Einstein"
+//! type = "human"
+//!
+//! [white]
+//! name = "Marie Curie"
+//! type = "computer"
+//! level = 3
+//!
+//! [board]
+//! width = 8
+//! height = 8
+//!
+//! [theme]                # same as the configuration file, the configured theme if missing
+//! background = "#00FF00"
+//! ```
+
+use std::error::Error;
+use std::fmt;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+use directories::ProjectDirs;
+use serde::{Deserialize, Serialize};
+
+use crate::*;
+
+/// Name of the file saved with the keyboard shortcut in the data directory
+pub const SAVE_FILE_NAME: &str = "game.toml";
+
+/// Name of the file saved when the game is closed in the data directory
+pub const AUTOSAVE_FILE_NAME: &str = "autosave.toml";
+
+/// A game saved on the disk
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct SavedGame {
+    /// Transcript of the moves played since the start position
+    pub moves: String,
+    /// Start position as a position string, the standard start of `board` if `None`
+    pub position: Option<String>,
+    /// How the game ended if it is not over on the board, `None` if no player resigned or ran out of time
+    pub end: Option<GameEnd>,
+    pub clocks: Clocks,
+    pub black: PlayerConfig,
+    pub white: PlayerConfig,
+    pub board: BoardConfig,
+    pub theme: Option<ThemeConfig>,
+}
+
+/// A resignation or a timeout, the end of a game that cannot be found by replaying its moves
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum GameEnd {
+    BlackResigned,
+    WhiteResigned,
+    BlackTimeout,
+    WhiteTimeout,
+}
+
+/// Time used by each player (in seconds)
+#[derive(Debug, Clone, Copy, Default, PartialEq, Deserialize, Serialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct Clocks {
+    pub black: f64,
+    pub white: f64,
+}
+
+/// Error raised while loading or saving a game
+#[derive(Debug)]
+pub enum SaveError {
+    /// The file cannot be read or written
+    Io(PathBuf, io::Error),
+    /// The file is not a valid TOML saved game
+    Parse(toml::de::Error),
+    /// The game cannot be written in TOML
+    Serialize(toml::ser::Error),
+    /// The position or the moves cannot be played
+    Notation(NotationError),
+}
+
+impl SavedGame {
+    /// Return the path of a file in the standard data directory
+    ///
+    /// e.g. `~/.local/share/rust-othello/game.toml` on Linux
+    pub fn data_path(file_name: &str) -> Option<PathBuf> {
+        ProjectDirs::from("", "", env!("CARGO_PKG_NAME"))
+            .map(|dirs| dirs.data_dir().join(file_name))
+    }
+
+    /// Return the path of the file saved with the keyboard shortcut
+    pub fn default_path() -> Option<PathBuf> {
+        SavedGame::data_path(SAVE_FILE_NAME)
+    }
+
+    /// Return the path of the file saved when the game is closed
+    pub fn autosave_path() -> Option<PathBuf> {
+        SavedGame::data_path(AUTOSAVE_FILE_NAME)
+    }
+
+    /// Write the game in a file, the missing directories are created
+    pub fn save(&self, path: &Path) -> Result<(), SaveError> {
+        let content = toml::to_string(self).map_err(SaveError::Serialize)?;
+        if let Some(directory) = path.parent() {
+            fs::create_dir_all(directory).map_err(|err| SaveError::Io(directory.to_path_buf(), err))?;
+        }
+        fs::write(path, content).map_err(|err| SaveError::Io(path.to_path_buf(), err))
+    }
+
+    /// Load a saved game and verify that it can be replayed
+    pub fn load(path: &Path) -> Result<SavedGame, SaveError> {
+        let content = fs::read_to_string(path).map_err(|err| SaveError::Io(path.to_path_buf(), err))?;
+        SavedGame::parse(&content)
+    }
+
+    /// Parse a saved game written in TOML and verify that it can be replayed
+    pub fn parse(content: &str) -> Result<SavedGame, SaveError> {
+        let game: SavedGame = toml::from_str(content).map_err(SaveError::Parse)?;
+        game.replay().map_err(SaveError::Notation)?;
+        Ok(game)
+    }
+
+    /// Create the players of the game
+    ///
+    /// Return (player_black, player_white)
+    pub fn players(&self) -> (Player, Player) {
+        (self.black.player(Piece::BLACK), self.white.player(Piece::WHITE))
+    }
+
+    /// Return the moves of the game
+    pub fn moves(&self) -> Result<Vec<GridPosition>, NotationError> {
+        parse_transcript(&self.moves)
+    }
+
+    /// Return the start position of the game and the side to move
+    pub fn start(&self) -> Result<(Board, Piece), NotationError> {
+        let size = (self.board.width, self.board.height);
+        match &self.position {
+            // A square position do not need the size of the board
+            Some(position) => Board::from_position_string(position, Some(size))
+                .or_else(|err| Board::from_position_string(position, None).map_err(|_| err)),
+            None if Board::is_valid_size(size) => Ok((Board::with_size(size), Piece::BLACK)),
+            None => Err(NotationError::InvalidSize((size.0 * size.1) as usize)),
+        }
+    }
+
+    /// Replay the moves of the game, return the board and the side to move
+    pub fn replay(&self) -> Result<(Board, Piece), NotationError> {
+        let (mut board, turn) = self.start()?;
+        let turn = play_transcript(&mut board, turn, &self.moves()?)?;
+        Ok((board, turn))
+    }
+}
+
+impl Default for SavedGame {
+    fn default() -> Self {
+        let config = Config::default();
+        SavedGame {
+            moves: String::new(),
+            position: None,
+            clocks: Clocks::default(),
+            black: config.black,
+            white: config.white,
+            board: config.board,
+            end: None,
+            theme: None,
+        }
+    }
+}
+
+impl GameEnd {
+    /// Return the end to save, `None` when no player resigned or ran out of time
+    pub fn from_reason(reason: EndReason) -> Option<Self> {
+        match reason {
+            EndReason::NoMoreMoves => None,
+            EndReason::Resignation(Piece::BLACK) => Some(GameEnd::BlackResigned),
+            EndReason::Resignation(_) => Some(GameEnd::WhiteResigned),
+            EndReason::Timeout(Piece::BLACK) => Some(GameEnd::BlackTimeout),
+            EndReason::Timeout(_) => Some(GameEnd::WhiteTimeout),
+        }
+    }
+
+    /// Return why the game is over
+    pub fn reason(self) -> EndReason {
+        match self {
+            GameEnd::BlackResigned => EndReason::Resignation(Piece::BLACK),
+            GameEnd::WhiteResigned => EndReason::Resignation(Piece::WHITE),
+            GameEnd::BlackTimeout => EndReason::Timeout(Piece::BLACK),
+            GameEnd::WhiteTimeout => EndReason::Timeout(Piece::WHITE),
+        }
+    }
+}
+
+impl fmt::Display for SaveError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            SaveError::Io(path, err) => write!(f, "cannot access {}: {}", path.display(), err),
+            SaveError::Parse(err) => write!(f, "invalid saved game: {}", err),
+            SaveError::Serialize(err) => write!(f, "cannot write the game: {}", err),
+            SaveError::Notation(err) => write!(f, "invalid saved game: {}", err),
+        }
+    }
+}
+
+impl Error for SaveError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            SaveError::Io(_, err) => Some(err),
+            SaveError::Parse(err) => Some(err),
+            SaveError::Serialize(err) => Some(err),
+            SaveError::Notation(err) => Some(err),
+        }
+    }
+}
+
+#[allow(unreachable_code)]
+#[cfg(test)]
+mod tests {
+    use crate::*;
+
+    #[test]
+    fn parse() {
+        let game = SavedGame::parse(r#"
+            moves = "f5d6c3"
+
+            [white]
+            name = "Marie Curie"
+            type = "computer"
+            level = 2
+        "#).unwrap();
+        let (board, turn) = game.replay().unwrap();
+        assert_eq!(turn, Piece::WHITE);
+        assert_eq!(board.score(Piece::BLACK), 5);
+        assert_eq!(game.players().1, Player::computer("Marie Curie", Piece::WHITE, 2));
+    }
+
+    #[test]
+    fn save_and_load() {
+        let game = SavedGame {
+            moves: String::from("b1"),
+            position: Some(String::from("-----OX--XO----- X")),
+            clocks: Clocks { black: 1.5, white: 42.0 },
+            theme: Some(ThemeConfig::default()),
+            end: Some(GameEnd::WhiteTimeout),
+            ..SavedGame::default()
+        };
+        let path = std::env::temp_dir().join(format!("rust-othello-test-{}/game.toml", std::process::id()));
+        game.save(&path).unwrap();
+        let loaded = SavedGame::load(&path);
+        std::fs::remove_dir_all(path.parent().unwrap()).unwrap();
+        assert_eq!(loaded.unwrap(), game);
+    }
+
+    #[test]
+    fn parse_illegal_move() {
+        assert!(matches!(SavedGame::parse("moves = \"f5f5\""), Err(SaveError::Notation(_))));
+    }
+}
@@ -0,0 +1,147 @@
+//! The scenes of the window: each screen handle its own events and drawing
+//!
+//! The scenes are stacked by [`App`](crate::App): only the scene on the top receive the events,
+//! a popup is drawn over the scenes under it.
+
+use ggez::{Context, GameResult};
+use ggez::event::{KeyCode, KeyMods, MouseButton};
+use ggez::graphics::{self, Color, Rect};
+use log::info;
+
+use crate::*;
+
+/// What a scene ask to the stack after an event
+pub enum Transition {
+    /// Stay on this scene
+    None,
+    /// Show a new scene over this one
+    Push(Box<dyn Scene>),
+    /// Leave this scene, the scene under it is shown
+    Pop,
+    /// Leave this scene for a new one
+    Replace(Box<dyn Scene>),
+    /// Close the window
+    Quit,
+}
+
+/// Data shared by every scene
+#[derive(Debug)]
+pub struct World {
+    pub config: Config,
+    /// Theme of the menus and of the new games
+    pub theme: Theme,
+    pub setup: GameSetup,
+    pub settings: Settings,
+    pub audio: Audio,
+}
+
+/// A screen of the window
+pub trait Scene {
+    /// Update the scene on every frame, only the scene on the top of the stack is updated
+    fn update(&mut self, _ctx: &mut Context, _world: &mut World) -> GameResult<Transition> {
+        Ok(Transition::None)
+    }
+
+    /// Draw the scene, a scene that is not a popup clear the screen
+    fn draw(&mut self, ctx: &mut Context, world: &World) -> GameResult;
+
+    /// Called every time a key gets pressed
+    fn key_down(&mut self, _ctx: &mut Context, _world: &mut World, _keycode: KeyCode, _keymod: KeyMods) -> Transition {
+        Transition::None
+    }
+
+    /// Called every time a mouse button gets pressed
+    fn mouse_down(&mut self, _ctx: &mut Context, _world: &mut World, _button: MouseButton, _x: f32, _y: f32) -> Transition {
+        Transition::None
+    }
+
+    /// Called when the scene is removed from the stack or when the window is closed
+    fn leave(&mut self, _world: &mut World) {}
+
+    /// Verify if the scene is a popup: the scenes under it are drawn but do not receive the events
+    fn is_popup(&self) -> bool {
+        false
+    }
+}
+
+impl World {
+    /// Create the data shared by the scenes
+    pub fn new(config: Config, theme: Theme) -> Self {
+        World {
+            setup: GameSetup::new(&config),
+            settings: Settings::new(&theme, &config.sound),
+            audio: Audio::default(),
+            config,
+            theme,
+        }
+    }
+
+    /// Play a sound effect with the volume of the settings
+    pub fn play_sound(&mut self, ctx: &mut Context, sound: Sound) {
+        self.audio.play(ctx, sound, self.settings.volume());
+    }
+
+    /// Switch to the next theme of the gallery, the configured theme is the first one
+    pub fn next_theme(&mut self) -> &Theme {
+        self.settings.change(SettingsEntry::Theme, true);
+        self.theme = self.settings.theme();
+        info!("THEME {}", self.settings.theme_name());
+        &self.theme
+    }
+}
+
+/// A popup with a message in the middle of the board, closed by any key or click
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Popup {
+    message: String,
+}
+
+impl Popup {
+    /// Create a popup
+    pub fn new<S: Into<String>>(message: S) -> Self {
+        Popup { message: message.into() }
+    }
+}
+
+impl Scene for Popup {
+    /// Draw the popup in the middle of the board
+    fn draw(&mut self, ctx: &mut Context, world: &World) -> GameResult {
+        let layout = Layout::from_context(ctx);
+        let area = layout.board;
+
+        // Draw the background of the popup
+        let popup = graphics::MeshBuilder::new()
+            .rounded_rectangle(
+                graphics::DrawMode::fill(),
+                Rect::new(
+                    area.x + area.w / 4.0,
+                    area.y + area.h / 4.0,
+                    area.w / 2.0,
+                    area.h / 2.0,
+                ),
+                50.0 * layout.scale,
+                Color::new(0.5, 0.5, 0.5, 1.0)
+            )?
+            .build(ctx)?;
+        graphics::draw(ctx, &popup, graphics::DrawParam::default())?;
+
+        let font = graphics::Font::new(ctx, world.theme.font_path.as_ref())?;
+        let mut text = graphics::Text::new((self.message.as_str(), font, layout.font_size(world.theme.font_scale)));
+        text.set_bounds(glam::Vec2::new(3.0 * area.w / 8.0 - 20.0 * layout.scale, 3.0 * area.h / 8.0), graphics::Align::Left);
+        let dest_point = glam::Vec2::new(area.x + 3.0 * area.w / 8.0, area.y + 3.0 * area.h / 8.0);
+        graphics::draw(ctx, &text, (dest_point,))?;
+        Ok(())
+    }
+
+    fn key_down(&mut self, _ctx: &mut Context, _world: &mut World, _keycode: KeyCode, _keymod: KeyMods) -> Transition {
+        Transition::Pop
+    }
+
+    fn mouse_down(&mut self, _ctx: &mut Context, _world: &mut World, _button: MouseButton, _x: f32, _y: f32) -> Transition {
+        Transition::Pop
+    }
+
+    fn is_popup(&self) -> bool {
+        true
+    }
+}
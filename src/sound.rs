@@ -0,0 +1,83 @@
+//! Sound effects of the game
+
+use std::collections::HashMap;
+
+use ggez::audio::{SoundSource, Source};
+use ggez::Context;
+use log::error;
+
+/// A sound effect played during a game
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Sound {
+    /// A disc is placed on the board
+    Place,
+    /// Some discs are eaten, louder when there are more
+    Flip(usize),
+    /// A player cannot play and pass
+    Pass,
+    /// The user chose a case where the player cannot play
+    Illegal,
+    /// The game is over
+    GameEnd,
+}
+
+impl Sound {
+    /// Return the path of the sound in resources/
+    pub fn path(&self) -> &'static str {
+        match self {
+            Sound::Place => "/sounds/place.wav",
+            Sound::Flip(_) => "/sounds/flip.wav",
+            Sound::Pass => "/sounds/pass.wav",
+            Sound::Illegal => "/sounds/illegal.wav",
+            Sound::GameEnd => "/sounds/game_end.wav",
+        }
+    }
+
+    /// Return the volume of the sound (from 0 to 1) before the volume of the settings
+    pub fn volume(&self) -> f32 {
+        match self {
+            Sound::Flip(count) => (0.3 + 0.1 * *count as f32).min(1.0),
+            _ => 1.0,
+        }
+    }
+}
+
+/// The sounds loaded from resources/
+#[derive(Debug, Default)]
+pub struct Audio {
+    /// The loaded sounds by path, `None` if the sound cannot be loaded
+    sources: HashMap<&'static str, Option<Source>>,
+}
+
+impl Audio {
+    /// Play a sound at `volume` (from 0 to 1), a sound is loaded the first time it is played
+    pub fn play(&mut self, ctx: &mut Context, sound: Sound, volume: f32) {
+        if volume <= 0.0 {
+            return;
+        }
+        let source = self.sources.entry(sound.path()).or_insert_with(|| {
+            Source::new(ctx, sound.path())
+                .map_err(|err| error!("Cannot load the sound {}: {}", sound.path(), err))
+                .ok()
+        });
+        if let Some(source) = source {
+            source.set_volume(volume * sound.volume());
+            if let Err(err) = source.play_detached(ctx) {
+                error!("Cannot play the sound {}: {}", sound.path(), err);
+            }
+        }
+    }
+}
+
+#[allow(unreachable_code)]
+#[cfg(test)]
+mod tests {
+    use crate::*;
+
+    #[test]
+    fn volume() {
+        assert_eq!(Sound::Place.volume(), 1.0);
+        assert!(Sound::Flip(1).volume() < Sound::Flip(4).volume());
+        assert_eq!(Sound::Flip(20).volume(), 1.0);
+    }
+}
@@ -0,0 +1,173 @@
+//! The states of a game and its history
+
+use std::fmt;
+
+use crate::*;
+
+/// State of a game
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum GameState {
+    /// The player of this color have to play
+    InProgress(Piece),
+    /// The player of this color cannot play and passed, the other one have to play
+    Pass(Piece),
+    /// The game is over
+    Finished(Outcome),
+}
+
+/// Result of a finished game
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Outcome {
+    /// The color of the winner, `None` for a draw
+    pub winner: Option<Piece>,
+    /// Final score (black, white)
+    pub score: (u16, u16),
+    pub reason: EndReason,
+}
+
+/// Why a game is over
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EndReason {
+    /// No player can play, the one with the most pieces win
+    NoMoreMoves,
+    /// The player of this color resigned
+    Resignation(Piece),
+    /// The player of this color ran out of time
+    Timeout(Piece),
+}
+
+/// A move of the history of a game
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Move {
+    /// The player of this color played at this position
+    Play(Piece, GridPosition),
+    /// The player of this color cannot play
+    Pass(Piece),
+}
+
+/// What happened when a move is played
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MoveOutcome {
+    /// The pieces of the other player that are eaten
+    pub flips: Flips,
+    /// State of the game after the move (a pass of the other player or the end of the game)
+    pub state: GameState,
+}
+
+impl GameState {
+    /// Return the color of the player who have to play, `None` if the game is over
+    pub fn turn(&self) -> Option<Piece> {
+        match self {
+            GameState::InProgress(piece) => Some(*piece),
+            GameState::Pass(piece) => Some(piece.next()),
+            GameState::Finished(_) => None,
+        }
+    }
+
+    /// Verify if the game is over
+    pub fn is_finished(&self) -> bool {
+        matches!(self, GameState::Finished(_))
+    }
+}
+
+impl Outcome {
+    /// Compute the result of a game that end on this board
+    pub fn new(board: &Board, reason: EndReason) -> Self {
+        let score = (board.score(Piece::BLACK), board.score(Piece::WHITE));
+        let winner = match reason {
+            EndReason::NoMoreMoves => match score.0.cmp(&score.1) {
+                std::cmp::Ordering::Greater => Some(Piece::BLACK),
+                std::cmp::Ordering::Less => Some(Piece::WHITE),
+                std::cmp::Ordering::Equal => None,
+            },
+            EndReason::Resignation(piece) | EndReason::Timeout(piece) => Some(piece.next()),
+        };
+        Outcome { winner, score, reason }
+    }
+}
+
+impl Move {
+    /// Return the color of the player of this move
+    pub fn piece(&self) -> Piece {
+        match self {
+            Move::Play(piece, _) | Move::Pass(piece) => *piece,
+        }
+    }
+
+    /// Return the position of the move, `None` for a pass
+    pub fn position(&self) -> Option<GridPosition> {
+        match self {
+            Move::Play(_, position) => Some(*position),
+            Move::Pass(_) => None,
+        }
+    }
+}
+
+impl fmt::Display for Outcome {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self.winner {
+            Some(winner) => write!(f, "{} win {}-{}", winner, self.score.0, self.score.1)?,
+            None => write!(f, "Draw {}-{}", self.score.0, self.score.1)?,
+        }
+        match self.reason {
+            EndReason::NoMoreMoves => Ok(()),
+            EndReason::Resignation(piece) => write!(f, " ({} resigned)", piece),
+            EndReason::Timeout(piece) => write!(f, " ({} ran out of time)", piece),
+        }
+    }
+}
+
+impl fmt::Display for Move {
+    /// Write the move like in a transcript (`f5`), or `pass`
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Move::Play(_, position) => write!(f, "{}", position.to_notation()),
+            Move::Pass(_) => write!(f, "pass"),
+        }
+    }
+}
+
+#[allow(unreachable_code)]
+#[cfg(test)]
+mod tests {
+    use crate::*;
+    use grid::grid;
+
+    const B: Piece = Piece::BLACK;
+    const W: Piece = Piece::WHITE;
+    const E: Piece = Piece::EMPTY;
+
+    #[test]
+    fn turn() {
+        assert_eq!(GameState::InProgress(B).turn(), Some(B));
+        assert_eq!(GameState::Pass(B).turn(), Some(W));
+        let outcome = Outcome::new(&Board::default(), EndReason::Resignation(W));
+        assert_eq!(GameState::Finished(outcome).turn(), None);
+        assert!(GameState::Finished(outcome).is_finished());
+    }
+
+    #[test]
+    fn outcome() {
+        let board = Board::set_board(grid![[B,B,B,W]
+                                                  [B,B,W,W]
+                                                  [B,B,W,W]
+                                                  [B,W,W,E]]);
+        let outcome = Outcome::new(&board, EndReason::NoMoreMoves);
+        assert_eq!(outcome, Outcome { winner: Some(B), score: (8, 7), reason: EndReason::NoMoreMoves });
+        assert_eq!(format!("{}", outcome), "BLACK win 8-7");
+
+        let outcome = Outcome::new(&board, EndReason::Timeout(B));
+        assert_eq!(outcome.winner, Some(W));
+        assert_eq!(format!("{}", outcome), "WHITE win 8-7 (BLACK ran out of time)");
+
+        let outcome = Outcome::new(&Board::default(), EndReason::NoMoreMoves);
+        assert_eq!(outcome.winner, None);
+        assert_eq!(format!("{}", outcome), "Draw 2-2");
+    }
+
+    #[test]
+    fn fmt_move() {
+        assert_eq!(format!("{}", Move::Play(B, GridPosition::new(5, 4))), "f5");
+        assert_eq!(format!("{}", Move::Pass(W)), "pass");
+    }
+}
@@ -1,25 +1,206 @@
-use ggez::graphics::Color;
+use std::borrow::Cow;
+use std::time::Duration;
+
+use ggez::graphics::{Color, Rect};
+use serde::{Deserialize, Serialize};
 
 
 pub const DEFAULT_THEME: Theme = Theme {
     piece_colors: (Color::BLACK, Color::WHITE),
     valid_moves_color: Some(Color::RED),
+    last_move_color: Some(Color::BLUE),
     background_color: Color::GREEN,
     grid_color: Color::WHITE,
-    font_path: "/fonts/LiberationMono-Regular.ttf",
+    coordinates_color: Some(Color::WHITE),
+    star_points_color: Some(Color::WHITE),
+    cursor_color: Color::new(1.0, 1.0, 0.0, 1.0),
+    large_markers: false,
+    font_path: Cow::Borrowed("/fonts/LiberationMono-Regular.ttf"),
     font_scale: 15.0,
+    animation_duration: Some(Duration::from_millis(300)),
+    piece_style: PieceStyle::Flat,
+    board_texture: None,
+};
+
+/// A dark board with outlined pieces
+pub const DARK_THEME: Theme = Theme {
+    piece_colors: (Color::new(0.08, 0.08, 0.08, 1.0), Color::new(0.88, 0.88, 0.88, 1.0)),
+    valid_moves_color: Some(Color::new(1.0, 0.69, 0.0, 0.8)),
+    last_move_color: Some(Color::new(0.25, 0.66, 0.96, 1.0)),
+    background_color: Color::new(0.15, 0.15, 0.16, 1.0),
+    grid_color: Color::new(0.4, 0.4, 0.42, 1.0),
+    coordinates_color: Some(Color::new(0.65, 0.65, 0.65, 1.0)),
+    star_points_color: Some(Color::new(0.4, 0.4, 0.42, 1.0)),
+    cursor_color: Color::new(0.0, 0.9, 0.9, 1.0),
+    piece_style: PieceStyle::Outline,
+    ..DEFAULT_THEME
+};
+
+/// Pure colors and bigger texts
+pub const HIGH_CONTRAST_THEME: Theme = Theme {
+    piece_colors: (Color::BLACK, Color::WHITE),
+    valid_moves_color: Some(Color::new(1.0, 1.0, 0.0, 1.0)),
+    last_move_color: Some(Color::new(1.0, 0.0, 1.0, 1.0)),
+    background_color: Color::new(0.0, 0.0, 0.55, 1.0),
+    grid_color: Color::WHITE,
+    coordinates_color: Some(Color::WHITE),
+    star_points_color: Some(Color::WHITE),
+    cursor_color: Color::new(0.0, 1.0, 1.0, 1.0),
+    large_markers: true,
+    font_scale: 19.0,
+    piece_style: PieceStyle::Outline,
+    ..DEFAULT_THEME
+};
+
+/// Colors that can be told apart with a color blindness (Okabe-Ito palette, no red and green)
+pub const COLOR_BLIND_THEME: Theme = Theme {
+    valid_moves_color: Some(Color::new(0.9, 0.62, 0.0, 1.0)),
+    last_move_color: Some(Color::new(0.94, 0.89, 0.26, 1.0)),
+    background_color: Color::new(0.0, 0.45, 0.7, 1.0),
+    cursor_color: Color::new(0.8, 0.47, 0.65, 1.0),
+    piece_style: PieceStyle::Shaded,
+    ..DEFAULT_THEME
 };
 
-#[derive(Debug, Clone, Copy)]
+/// How the pieces are drawn
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum PieceStyle {
+    /// A disc of one color
+    #[default]
+    Flat,
+    /// A disc with a border of the color of the grid
+    Outline,
+    /// A disc with a light reflection
+    Shaded,
+}
+
+#[derive(Debug, Clone)]
 pub struct Theme {
     pub piece_colors: (Color, Color),
     pub valid_moves_color: Option<Color>,
+    // mark of the last played move, not drawn if None
+    pub last_move_color: Option<Color>,
     pub background_color: Color,
     pub grid_color: Color,
+    // labels a-h / 1-8 around the board, not drawn if None
+    pub coordinates_color: Option<Color>,
+    // the four points of the grid between the center and the corners, not drawn if None
+    pub star_points_color: Option<Color>,
+    // the cell chosen with the keyboard
+    pub cursor_color: Color,
+    // bigger marks of the valid moves, of the last move and of the cursor
+    pub large_markers: bool,
 
     // font of texts (from resources/)
     // don't forget to start with "/"
     // example: "/fonts/font.ttf"
-    pub font_path: &'static str,
+    pub font_path: Cow<'static, str>,
     pub font_scale: f32,
+
+    // duration of the animation of a move, not animated if None
+    pub animation_duration: Option<Duration>,
+
+    pub piece_style: PieceStyle,
+    // image drawn under the grid (from resources/), stretched to the board
+    // example: "/images/wood.png"
+    pub board_texture: Option<Cow<'static, str>>,
+}
+
+/// Names of the built-in themes (see [`Theme::by_name`])
+pub const THEME_NAMES: [&str; 4] = ["classic", "dark", "high-contrast", "color-blind"];
+
+impl Theme {
+    /// Return the area of the grid when the board is drawn in `area`
+    ///
+    /// If the coordinates are drawn, a margin is kept around the grid for them.
+    pub fn grid_area(&self, area: Rect) -> Rect {
+        if self.coordinates_color.is_none() {
+            return area;
+        }
+        let margin = area.w.min(area.h) / 20.0;
+        Rect::new(area.x + margin, area.y + margin, area.w - 2.0 * margin, area.h - 2.0 * margin)
+    }
+
+    /// Return the built-in theme of this name
+    pub fn by_name(name: &str) -> Option<Theme> {
+        match name {
+            "classic" => Some(DEFAULT_THEME),
+            "dark" => Some(DARK_THEME),
+            "high-contrast" => Some(HIGH_CONTRAST_THEME),
+            "color-blind" => Some(COLOR_BLIND_THEME),
+            _ => None,
+        }
+    }
+}
+
+impl Default for Theme {
+    fn default() -> Self {
+        DEFAULT_THEME
+    }
+}
+
+/// Parse a color written as `#RRGGBB` or `#RRGGBBAA`
+pub fn color_from_hex(hex: &str) -> Option<Color> {
+    let digits = hex.strip_prefix('#')?;
+    if !(digits.len() == 6 || digits.len() == 8) || !digits.is_ascii() {
+        return None;
+    }
+    let mut components = [u8::MAX; 4];
+    for (i, component) in components.iter_mut().enumerate().take(digits.len() / 2) {
+        *component = u8::from_str_radix(&digits[2 * i..2 * i + 2], 16).ok()?;
+    }
+    Some(Color::from_rgba(components[0], components[1], components[2], components[3]))
+}
+
+/// Write a color as `#RRGGBB` (or `#RRGGBBAA` if it is transparent)
+pub fn color_to_hex(color: Color) -> String {
+    let (r, g, b, a) = color.to_rgba();
+    if a == u8::MAX {
+        format!("#{:02X}{:02X}{:02X}", r, g, b)
+    } else {
+        format!("#{:02X}{:02X}{:02X}{:02X}", r, g, b, a)
+    }
+}
+
+#[allow(unreachable_code)]
+#[cfg(test)]
+mod tests {
+    use ggez::graphics::{Color, Rect};
+
+    use crate::{color_from_hex, color_to_hex, Theme, DEFAULT_THEME, THEME_NAMES};
+
+    #[test]
+    fn parse_hex_color() {
+        assert_eq!(color_from_hex("#000000"), Some(Color::BLACK));
+        assert_eq!(color_from_hex("#FFFFFF"), Some(Color::WHITE));
+        assert_eq!(color_from_hex("#ff000080"), Some(Color::from_rgba(255, 0, 0, 128)));
+        assert_eq!(color_from_hex("00FF00"), None);
+        assert_eq!(color_from_hex("#00FF0"), None);
+        assert_eq!(color_from_hex("#GG0000"), None);
+    }
+
+    #[test]
+    fn write_hex_color() {
+        assert_eq!(color_to_hex(Color::GREEN), "#00FF00");
+        assert_eq!(color_to_hex(Color::from_rgba(1, 2, 3, 4)), "#01020304");
+    }
+
+    #[test]
+    fn gallery() {
+        for name in THEME_NAMES {
+            assert!(Theme::by_name(name).is_some(), "{} is not a theme", name);
+        }
+        assert_eq!(Theme::by_name("dark").unwrap().font_path, DEFAULT_THEME.font_path);
+        assert!(Theme::by_name("unknown").is_none());
+    }
+
+    #[test]
+    fn grid_area() {
+        let area = Rect::new(100.0, 0.0, 800.0, 800.0);
+        assert_eq!(DEFAULT_THEME.grid_area(area), Rect::new(140.0, 40.0, 720.0, 720.0));
+
+        let theme = Theme { coordinates_color: None, ..DEFAULT_THEME };
+        assert_eq!(theme.grid_area(area), area);
+    }
 }
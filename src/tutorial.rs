@@ -0,0 +1,424 @@
+//! A guided tutorial of the rules: scripted positions where the learner play the asked move
+//!
+//! The moves are verified with [`Board::is_valid_move`], see also the
+//! [rules](https://documentation.help/Reversi-Rules/rules.htm).
+
+use ggez::{Context, GameResult, timer};
+use ggez::event::{KeyCode, KeyMods, MouseButton};
+use ggez::graphics::{self, Color};
+use log::{debug, info};
+
+use crate::*;
+
+/// What the learner have to do to finish a lesson
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Goal {
+    /// Play any valid move
+    AnyMove,
+    /// Play this move (in notation, e.g. "d4")
+    Move(&'static str),
+    /// Pass because there is no valid move
+    Pass,
+}
+
+/// A step of the tutorial
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Lesson {
+    pub title: &'static str,
+    /// The explanation of the rule
+    pub text: &'static str,
+    /// The position string of the lesson, the side to move is the learner
+    pub position: &'static str,
+    pub goal: Goal,
+    /// Shown when the goal is done
+    pub success: &'static str,
+    /// Shown when the learner play a valid move that is not the asked one
+    pub hint: &'static str,
+}
+
+/// The lessons of the tutorial, in order
+pub const LESSONS: [Lesson; 5] = [
+    Lesson {
+        title: "Placement",
+        text: "Black play first. A disc must be placed next to a disc of the other color, so that \
+            a line of discs of the other color is enclosed between the new disc and a disc of yours. \
+            The valid moves are marked on the board.\n\nPlay any valid move.",
+        position: "
+            --------
+            --------
+            --------
+            ---OX---
+            ---XO---
+            --------
+            --------
+            -------- X",
+        goal: Goal::AnyMove,
+        success: "Well done! The enclosed discs are eaten: they are flipped to your color.",
+        hint: "",
+    },
+    Lesson {
+        title: "Flipping in several directions",
+        text: "A move eat in every direction (horizontal, vertical and diagonal) where a line of \
+            discs of the other color is enclosed by one of your discs.\n\nFind the move that eats in \
+            three directions at once.",
+        position: "
+            --------
+            -X-X----
+            --OO----
+            ----OX--
+            --------
+            --------
+            --------
+            -------- X",
+        goal: Goal::Move("d4"),
+        success: "Well done! The discs of the three lines are flipped together.",
+        hint: "This move is valid but it eats in only one direction.",
+    },
+    Lesson {
+        title: "Passing",
+        text: "You play White now. When a player have no valid move, the player passes and the other \
+            player play again. A player who can play is not allowed to pass.\n\nThere is no valid move for \
+            White here: press Enter to pass.",
+        position: "
+            --------
+            --------
+            --------
+            --------
+            --------
+            --------
+            --OO----
+            XXXX---- O",
+        goal: Goal::Pass,
+        success: "Right! White passes and Black play again.",
+        hint: "",
+    },
+    Lesson {
+        title: "Corners",
+        text: "A disc in a corner can never be eaten: there is no case on the other side to enclose \
+            it. The corners are often the best cases of the board.\n\nTake a corner.",
+        position: "
+            -OOX----
+            -O------
+            --X-----
+            ---OX---
+            --------
+            --------
+            --------
+            -------- X",
+        goal: Goal::Move("a1"),
+        success: "Well done! This disc will stay black until the end of the game.",
+        hint: "This move is valid, but look at the corner a1.",
+    },
+    Lesson {
+        title: "End of the game",
+        text: "The game is over when no player can play, most of the time when the board is full. \
+            The player with the most discs win.\n\nPlay the last move of the game.",
+        position: "
+            XXXXXXXX
+            XXXXXXXX
+            XXXXXXXX
+            XXXXXXXX
+            OOOOOOOO
+            OOOOOOOO
+            OOOOOOOO
+            XXXXXXO- X",
+        goal: Goal::Move("h8"),
+        success: "The board is full: the game is over.",
+        hint: "",
+    },
+];
+
+impl Lesson {
+    /// Return the board and the side to move of the lesson
+    pub fn start(&self) -> (Board, Piece) {
+        Board::from_position_string(self.position, None).expect("The positions of the lessons are valid")
+    }
+
+    /// Return the asked move, `None` if any move is accepted
+    pub fn target(&self) -> Option<GridPosition> {
+        match self.goal {
+            Goal::Move(notation) => Some(GridPosition::from_notation(notation).expect("The moves of the lessons are valid")),
+            _ => None,
+        }
+    }
+}
+
+/// Result of an attempt of the learner
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Attempt {
+    /// The goal of the lesson is done
+    Success,
+    /// The move or the pass is refused, with the reason
+    Refused(String),
+}
+
+/// The screen of the tutorial: the board of a lesson and its explanation
+#[derive(Debug, Clone)]
+pub struct Tutorial {
+    /// The shown lesson
+    index: usize,
+    board: Board,
+    turn: Piece,
+    /// The move of the learner, animated then marked
+    last_move: Option<MoveAnimation>,
+    /// The message of the last attempt
+    message: Option<String>,
+    done: bool,
+    /// The cell chosen with the keyboard
+    cursor: Option<GridPosition>,
+    theme: Theme,
+}
+
+impl Tutorial {
+    /// Start the tutorial at the first lesson
+    pub fn new(theme: Theme) -> Self {
+        let (board, turn) = LESSONS[0].start();
+        Tutorial { index: 0, board, turn, last_move: None, message: None, done: false, cursor: None, theme }
+    }
+
+    /// Return the shown lesson
+    pub fn lesson(&self) -> &'static Lesson {
+        &LESSONS[self.index]
+    }
+
+    /// Verify if the goal of the shown lesson is done
+    pub fn is_done(&self) -> bool {
+        self.done
+    }
+
+    /// Show a lesson from its start position
+    pub fn set_lesson(&mut self, index: usize) {
+        self.index = index.min(LESSONS.len() - 1);
+        (self.board, self.turn) = self.lesson().start();
+        self.last_move = None;
+        self.message = None;
+        self.done = false;
+        self.cursor = None;
+    }
+
+    /// Play a move of the learner, the move must be valid and be the asked one
+    pub fn play(&mut self, position: GridPosition) -> Attempt {
+        let attempt = if self.done {
+            Attempt::Refused(String::from("The lesson is done, press Enter for the next one."))
+        } else if self.lesson().goal == Goal::Pass {
+            Attempt::Refused(format!("{} is not valid: no move of {} encloses a disc.", position.to_notation(), self.turn))
+        } else if !self.board.is_valid_move(position, self.turn) {
+            Attempt::Refused(format!("{} is not valid: it does not enclose a line of discs.", position.to_notation()))
+        } else if self.lesson().target().is_some_and(|target| target != position) {
+            Attempt::Refused(self.lesson().hint.to_string())
+        } else {
+            let flips = self.board.play(position, self.turn).expect("The move is valid");
+            info!("TUTORIAL {} played {} and ate {} disc(s)", self.turn, position, flips.len());
+            self.last_move = Some(MoveAnimation::new(position, self.turn, flips, self.theme.animation_duration));
+            self.turn = self.turn.next();
+            Attempt::Success
+        };
+        self.record(attempt)
+    }
+
+    /// Pass, allowed only when there is no valid move
+    pub fn pass(&mut self) -> Attempt {
+        let attempt = if self.done {
+            Attempt::Refused(String::from("The lesson is done, press Enter for the next one."))
+        } else if self.board.can_play(self.turn) {
+            Attempt::Refused(String::from("You cannot pass: there is a valid move."))
+        } else {
+            info!("TUTORIAL {} passed", self.turn);
+            self.turn = self.turn.next();
+            Attempt::Success
+        };
+        self.record(attempt)
+    }
+
+    /// Keep the message of an attempt
+    fn record(&mut self, attempt: Attempt) -> Attempt {
+        self.message = Some(match &attempt {
+            Attempt::Success => {
+                self.done = true;
+                let mut message = self.lesson().success.to_string();
+                if self.board.is_finish() {
+                    message += &format!("\n{}", Outcome::new(&self.board, EndReason::NoMoreMoves));
+                }
+                message
+            }
+            Attempt::Refused(reason) => reason.clone(),
+        });
+        attempt
+    }
+
+    /// Move the keyboard cursor, it appears in the middle of the board the first time
+    fn move_cursor(&mut self, dx: i16, dy: i16) {
+        let (width, height) = self.board.size();
+        self.cursor = Some(match self.cursor {
+            Some(cursor) => GridPosition::new((cursor.x + dx).clamp(0, width - 1), (cursor.y + dy).clamp(0, height - 1)),
+            None => GridPosition::new(width / 2 - 1, height / 2 - 1),
+        });
+    }
+
+    /// Go to the next lesson, back to the menu after the last one
+    fn next(&mut self) -> Transition {
+        if self.index + 1 == LESSONS.len() {
+            info!("END of the tutorial");
+            return Transition::Pop;
+        }
+        self.set_lesson(self.index + 1);
+        Transition::None
+    }
+
+    /// Return the text of the panel
+    fn panel_text(&self) -> String {
+        let lesson = self.lesson();
+        let mut text = format!("Lesson {}/{}: {}\n\n{}", self.index + 1, LESSONS.len(), lesson.title, lesson.text);
+        if let Some(message) = &self.message {
+            text += &format!("\n\n{}", message);
+        }
+        if self.done {
+            text += if self.index + 1 == LESSONS.len() { "\n\nEnter: back to the menu" } else { "\n\nEnter: next lesson" };
+        }
+        text += "\n\nR: restart the lesson\nBackspace: previous lesson";
+        text
+    }
+
+    /// Play the sound of an attempt
+    fn play_sound(&self, ctx: &mut Context, world: &mut World, attempt: &Attempt) {
+        let sound = match attempt {
+            Attempt::Success if self.lesson().goal == Goal::Pass => Sound::Pass,
+            Attempt::Success => Sound::Place,
+            Attempt::Refused(_) => Sound::Illegal,
+        };
+        world.play_sound(ctx, sound);
+    }
+}
+
+impl Scene for Tutorial {
+    /// Animate the move of the learner
+    fn update(&mut self, ctx: &mut Context, _world: &mut World) -> GameResult<Transition> {
+        if let Some(last_move) = &mut self.last_move {
+            last_move.update(timer::delta(ctx));
+        }
+        Ok(Transition::None)
+    }
+
+    /// Draw the board of the lesson (the valid moves until the goal is done) and the explanation
+    fn draw(&mut self, ctx: &mut Context, _world: &World) -> GameResult {
+        graphics::clear(ctx, self.theme.background_color);
+        let layout = Layout::from_context(ctx);
+        let theme = match self.done {
+            true => Theme { valid_moves_color: None, ..self.theme.clone() },
+            false => self.theme.clone(),
+        };
+        self.board.draw_animated(ctx, layout.board, self.turn, &theme, self.last_move.as_ref())?;
+        if let (Some(cursor), false) = (self.cursor, self.done) {
+            self.board.draw_cursor(ctx, &theme, cursor, layout.board)?;
+        }
+
+        let area = layout.panel;
+        let margin = 10.0 * layout.scale;
+        let background = graphics::MeshBuilder::new()
+            .rectangle(graphics::DrawMode::fill(), area, Color::new(0.0, 0.0, 0.0, 0.6))?
+            .build(ctx)?;
+        graphics::draw(ctx, &background, graphics::DrawParam::default())?;
+        let font = graphics::Font::new(ctx, self.theme.font_path.as_ref())?;
+        let mut text = graphics::Text::new((self.panel_text(), font, layout.font_size(self.theme.font_scale)));
+        text.set_bounds(glam::Vec2::new(area.w - 2.0 * margin, area.h - 2.0 * margin), graphics::Align::Left);
+        graphics::draw(ctx, &text, (glam::Vec2::new(area.x + margin, area.y + margin), Color::WHITE))
+    }
+
+    /// Play the clicked case
+    fn mouse_down(&mut self, ctx: &mut Context, world: &mut World, button: MouseButton, x: f32, y: f32) -> Transition {
+        if button != MouseButton::Left {
+            return Transition::None;
+        }
+        let mut layout = Layout::from_context(ctx);
+        layout.board = self.theme.grid_area(layout.board);
+        match layout.board_position(x, y) {
+            Some(position) => {
+                let attempt = self.play(position.into_grid(self.board.cell_size((layout.board.w, layout.board.h))));
+                self.play_sound(ctx, world, &attempt);
+            }
+            None => debug!("The click ({}, {}) is not on the board", x, y),
+        }
+        Transition::None
+    }
+
+    /// Called every time a key gets pressed
+    ///
+    /// - Arrows: move the cursor
+    /// - Enter / Space: play at the cursor, pass, or next lesson when the lesson is done
+    /// - R: restart the lesson
+    /// - Backspace: previous lesson
+    /// - Escape: back to the menu
+    fn key_down(&mut self, ctx: &mut Context, world: &mut World, keycode: KeyCode, _keymod: KeyMods) -> Transition {
+        match keycode {
+            KeyCode::Escape => {info!("LEAVE the tutorial from key Escape"); return Transition::Pop;},
+            KeyCode::Up => self.move_cursor(0, -1),
+            KeyCode::Down => self.move_cursor(0, 1),
+            KeyCode::Left => self.move_cursor(-1, 0),
+            KeyCode::Right => self.move_cursor(1, 0),
+            KeyCode::R => self.set_lesson(self.index),
+            KeyCode::Back => self.set_lesson(self.index.saturating_sub(1)),
+            KeyCode::Return | KeyCode::NumpadEnter | KeyCode::Space => {
+                if self.done {
+                    return self.next();
+                }
+                let attempt = match self.cursor {
+                    Some(cursor) if self.lesson().goal != Goal::Pass => self.play(cursor),
+                    None if self.lesson().goal != Goal::Pass => {
+                        self.move_cursor(0, 0);
+                        return Transition::None;
+                    }
+                    _ => self.pass(),
+                };
+                self.play_sound(ctx, world, &attempt);
+            }
+            _ => {}
+        }
+        Transition::None
+    }
+}
+
+#[allow(unreachable_code)]
+#[cfg(test)]
+mod tests {
+    use crate::*;
+
+    #[test]
+    fn lessons() {
+        for lesson in LESSONS {
+            let (board, turn) = lesson.start();
+            assert_eq!(board.size(), (8, 8), "{}", lesson.title);
+            match lesson.goal {
+                Goal::AnyMove => assert!(board.can_play(turn)),
+                Goal::Move(_) => assert!(board.is_valid_move(lesson.target().unwrap(), turn), "{}", lesson.title),
+                Goal::Pass => assert!(!board.can_play(turn) && board.can_play(turn.next())),
+            }
+        }
+        // The move of the second lesson eats in three directions
+        let (mut board, turn) = LESSONS[1].start();
+        assert_eq!(board.get_valid_moves(turn).len(), 2);
+        assert_eq!(board.play(LESSONS[1].target().unwrap(), turn).unwrap().directions.len(), 3);
+    }
+
+    #[test]
+    fn attempts() {
+        let mut tutorial = Tutorial::new(Theme::default());
+        assert!(matches!(tutorial.play(GridPosition::new(0, 0)), Attempt::Refused(_)));
+        assert!(matches!(tutorial.pass(), Attempt::Refused(_)));
+        assert_eq!(tutorial.play(GridPosition::from_notation("f5").unwrap()), Attempt::Success);
+        assert!(tutorial.is_done());
+
+        tutorial.set_lesson(1);
+        assert_eq!(tutorial.play(GridPosition::from_notation("b4").unwrap()), Attempt::Refused(String::from(LESSONS[1].hint)));
+        assert!(!tutorial.is_done());
+        assert_eq!(tutorial.play(GridPosition::from_notation("d4").unwrap()), Attempt::Success);
+
+        tutorial.set_lesson(2);
+        assert!(matches!(tutorial.play(GridPosition::from_notation("c6").unwrap()), Attempt::Refused(_)));
+        assert_eq!(tutorial.pass(), Attempt::Success);
+
+        tutorial.set_lesson(4);
+        assert_eq!(tutorial.play(GridPosition::from_notation("h8").unwrap()), Attempt::Success);
+        assert!(tutorial.panel_text().contains("BLACK win"));
+        assert!(matches!(tutorial.next(), Transition::Pop));
+    }
+}